@@ -1,4 +1,5 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug, Clone)]
 pub struct AuthConfig {
@@ -9,26 +10,26 @@ pub struct AuthConfig {
 #[derive(Debug)]
 pub struct Auth {}
 impl Auth {
-    pub fn build(config: &AuthConfig) -> anyhow::Result<String> {
-        let mut value = "AUTH".to_string();
+    pub fn build(config: &AuthConfig) -> crate::Result<Vec<u8>> {
+        let mut value = CommandEncoder::new("AUTH");
         if config.username.is_some() && config.password.is_none() {
-            return Err(anyhow::anyhow!("USERNAME_NO_PASSWORD".to_string()));
+            return Err(Error::InvalidCommand("USERNAME_NO_PASSWORD".to_string()));
         }
         if let Some(username) = &config.username {
             if username.is_empty() {
-                return Err(anyhow!("USERNAME_REQUIRED".to_string()));
+                return Err(Error::InvalidCommand("USERNAME_REQUIRED".to_string()));
             }
-            value = format!("{value} {username}");
+            value = value.arg(username);
         }
         if let Some(password) = &config.password {
             if password.is_empty() {
-                return Err(anyhow!("PASSWORD_EMPTY".to_string()));
+                return Err(Error::InvalidCommand("PASSWORD_EMPTY".to_string()));
             }
-            value = format!("{value} {password}");
+            value = value.arg(password);
         } else {
-            return Err(anyhow!("PASSWORD_REQUIRED".to_string()));
+            return Err(Error::InvalidCommand("PASSWORD_REQUIRED".to_string()));
         }
-        Ok(format!("{value}\r\n"))
+        Ok(value.build())
     }
 }
 
@@ -43,7 +44,10 @@ pub mod test_auth {
             password: Some("mypassword".to_string()),
         });
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("AUTH mypassword\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*2\r\n$4\r\nAUTH\r\n$10\r\nmypassword\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 
     #[test]
@@ -54,7 +58,7 @@ pub mod test_auth {
         });
         assert!(result.is_ok(), "{:#?}", result.err());
         assert_eq!(
-            "AUTH myusername mypassword\r\n".to_string(),
+            b"*3\r\n$4\r\nAUTH\r\n$10\r\nmyusername\r\n$10\r\nmypassword\r\n".to_vec(),
             result.unwrap()
         );
     }
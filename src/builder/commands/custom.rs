@@ -0,0 +1,42 @@
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
+
+#[derive(Debug)]
+pub struct Custom {}
+
+impl Custom {
+    /// Builds any command from its name and binary-safe arguments.
+    /// Parameters:
+    /// - name - The command name, e.g. `HSET`.
+    /// - args - The arguments, sent without any conversion.
+    pub fn build(name: &str, args: &[Vec<u8>]) -> crate::Result<Vec<u8>> {
+        if name.is_empty() {
+            return Err(Error::InvalidCommand("CUSTOM_COMMAND_REQUIRED".to_string()));
+        }
+        Ok(CommandEncoder::new(name).args(args).build())
+    }
+}
+
+#[cfg(test)]
+pub mod test_custom {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let result = Custom::build(
+            "HSET",
+            &[b"myhash".to_vec(), b"field".to_vec(), vec![0, 255]],
+        );
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            b"*4\r\n$4\r\nHSET\r\n$6\r\nmyhash\r\n$5\r\nfield\r\n$2\r\n\x00\xFF\r\n".to_vec(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        let result = Custom::build("", &[]);
+        assert!(result.is_err());
+    }
+}
@@ -1,14 +1,15 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug)]
 pub struct Decrement {}
 
 impl Decrement {
-    pub fn build(value: &str) -> anyhow::Result<String> {
+    pub fn build(value: &str) -> crate::Result<Vec<u8>> {
         if value.is_empty() {
-            return Err(anyhow!("DECR_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("DECR_KEY_REQUIRED".to_string()));
         }
-        Ok(format!("DECR {value}\r\n"))
+        Ok(CommandEncoder::new("DECR").arg(value).build())
     }
 }
 
@@ -20,6 +21,9 @@ pub mod test_increment {
     fn test() {
         let result = Decrement::build("mykey");
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("DECR mykey\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*2\r\n$4\r\nDECR\r\n$5\r\nmykey\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 }
@@ -1,14 +1,18 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug)]
 pub struct DecrementBy {}
 
 impl DecrementBy {
-    pub fn build(key: &str, value: &u64) -> anyhow::Result<String> {
+    pub fn build(key: &str, value: &u64) -> crate::Result<Vec<u8>> {
         if key.is_empty() {
-            return Err(anyhow!("DECRBY_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("DECRBY_KEY_REQUIRED".to_string()));
         }
-        Ok(format!("DECRBY {key} {value}\r\n"))
+        Ok(CommandEncoder::new("DECRBY")
+            .arg(key)
+            .arg(value.to_string())
+            .build())
     }
 }
 
@@ -20,6 +24,9 @@ pub mod test_increment_by {
     fn test() {
         let result = DecrementBy::build("mykey", &1);
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("DECRBY mykey 1\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*3\r\n$6\r\nDECRBY\r\n$5\r\nmykey\r\n$1\r\n1\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 }
@@ -1,15 +1,15 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug)]
 pub struct Delete {}
 
 impl Delete {
-    pub fn build(value: &[String]) -> anyhow::Result<String> {
+    pub fn build(value: &[String]) -> crate::Result<Vec<u8>> {
         if value.is_empty() {
-            return Err(anyhow!("DELETE_KEYS_REQUIRED"));
+            return Err(Error::InvalidCommand("DELETE_KEYS_REQUIRED".to_string()));
         }
-        let keys = value.join(" ");
-        Ok(format!("DEL {keys}\r\n"))
+        Ok(CommandEncoder::new("DEL").args(value).build())
     }
 }
 
@@ -19,8 +19,11 @@ pub mod test_delete {
 
     #[test]
     fn test() {
-        let result = Delete::build(&vec!["mykey".to_string()]);
+        let result = Delete::build(&["mykey".to_string()]);
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("DEL mykey\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*2\r\n$3\r\nDEL\r\n$5\r\nmykey\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 }
@@ -0,0 +1,97 @@
+/// Encodes a command and its arguments as a RESP array of bulk strings.
+/// Every argument is length-prefixed, so keys and values containing spaces, CR/LF or binary
+/// bytes are sent to the server unchanged.
+/// Reference: https://valkey.io/topics/protocol/#sending-commands-to-a-valkey-server
+#[derive(Debug, Clone, Default)]
+pub struct CommandEncoder {
+    args: Vec<Vec<u8>>,
+}
+
+impl CommandEncoder {
+    /// Starts a new command.
+    /// Parameters:
+    /// - name - The command name, e.g. `SET`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            args: vec![name.as_bytes().to_vec()],
+        }
+    }
+
+    /// Appends a single argument.
+    pub fn arg<T: AsRef<[u8]>>(mut self, value: T) -> Self {
+        self.args.push(value.as_ref().to_vec());
+        self
+    }
+
+    /// Appends every argument of the iterator in order.
+    pub fn args<I, T>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        for value in values {
+            self.args.push(value.as_ref().to_vec());
+        }
+        self
+    }
+
+    /// Returns the wire representation: `*N\r\n` followed by `$len\r\n<bytes>\r\n` per argument.
+    pub fn build(&self) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::new();
+        data.extend_from_slice(format!("*{}\r\n", self.args.len()).as_bytes());
+        for arg in self.args.iter() {
+            data.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            data.extend_from_slice(arg);
+            data.extend_from_slice(b"\r\n");
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+pub mod test_encoder {
+    use super::*;
+
+    #[test]
+    fn test_no_arguments() {
+        let result = CommandEncoder::new("PING").build();
+        assert_eq!(b"*1\r\n$4\r\nPING\r\n".to_vec(), result);
+    }
+
+    #[test]
+    fn test_arguments() {
+        struct TestCase {
+            pub input: CommandEncoder,
+            pub expected: Vec<u8>,
+        }
+        let test_cases = vec![
+            TestCase {
+                // key with a space stays a single argument
+                input: CommandEncoder::new("GET").arg("my key"),
+                expected: b"*2\r\n$3\r\nGET\r\n$6\r\nmy key\r\n".to_vec(),
+            },
+            TestCase {
+                // CR/LF inside a value is not treated as a terminator
+                input: CommandEncoder::new("SET").arg("k").arg("a\r\nb"),
+                expected: b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$4\r\na\r\nb\r\n".to_vec(),
+            },
+            TestCase {
+                // binary payload
+                input: CommandEncoder::new("SET").arg("k").arg([0u8, 255, 13, 10]),
+                expected: b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$4\r\n\x00\xff\r\n\r\n".to_vec(),
+            },
+            TestCase {
+                // empty argument
+                input: CommandEncoder::new("SET").arg("k").arg(""),
+                expected: b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$0\r\n\r\n".to_vec(),
+            },
+            TestCase {
+                input: CommandEncoder::new("DEL").args(["a", "bb"]),
+                expected: b"*3\r\n$3\r\nDEL\r\n$1\r\na\r\n$2\r\nbb\r\n".to_vec(),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(test_case.expected, test_case.input.build());
+        }
+    }
+}
@@ -1,15 +1,15 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug)]
 pub struct Exists {}
 
 impl Exists {
-    pub fn build(value: &[String]) -> anyhow::Result<String> {
+    pub fn build(value: &[String]) -> crate::Result<Vec<u8>> {
         if value.is_empty() {
-            return Err(anyhow!("EXISTS_KEYS_REQUIRED"));
+            return Err(Error::InvalidCommand("EXISTS_KEYS_REQUIRED".to_string()));
         }
-        let keys = value.join(" ");
-        Ok(format!("EXISTS {keys}\r\n"))
+        Ok(CommandEncoder::new("EXISTS").args(value).build())
     }
 }
 
@@ -19,8 +19,11 @@ pub mod test_exists {
 
     #[test]
     fn test() {
-        let result = Exists::build(&vec!["mykey".to_string(), "yourkey".to_string()]);
+        let result = Exists::build(&["mykey".to_string(), "yourkey".to_string()]);
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("EXISTS mykey yourkey\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*3\r\n$6\r\nEXISTS\r\n$5\r\nmykey\r\n$7\r\nyourkey\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 }
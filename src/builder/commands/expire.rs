@@ -1,19 +1,22 @@
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 use crate::types::ExpiryKind;
-use anyhow::anyhow;
 
 #[derive(Debug)]
 pub struct Expire {}
 
 impl Expire {
-    pub fn build(key: &str, duration: &u64, kind: &Option<ExpiryKind>) -> anyhow::Result<String> {
+    pub fn build(key: &str, duration: &u64, kind: &Option<ExpiryKind>) -> crate::Result<Vec<u8>> {
         if key.is_empty() {
-            return Err(anyhow!("EXPIRE_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("EXPIRE_KEY_REQUIRED".to_string()));
         }
+        let mut command = CommandEncoder::new("EXPIRE")
+            .arg(key)
+            .arg(duration.to_string());
         if let Some(k) = kind {
-            Ok(format!("EXPIRE {key} {duration} {k}\r\n"))
-        } else {
-            Ok(format!("EXPIRE {key} {duration}\r\n"))
+            command = command.arg(k.to_string());
         }
+        Ok(command.build())
     }
 }
 
@@ -25,13 +28,19 @@ pub mod test_expire {
     fn test_with_kind() {
         let result = Expire::build("mykey", &10, &Some(ExpiryKind::Xx));
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("EXPIRE mykey 10 XX\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*4\r\n$6\r\nEXPIRE\r\n$5\r\nmykey\r\n$2\r\n10\r\n$2\r\nXX\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 
     #[test]
     fn test_without_kind() {
         let result = Expire::build("mykey", &10, &None);
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("EXPIRE mykey 10\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*3\r\n$6\r\nEXPIRE\r\n$5\r\nmykey\r\n$2\r\n10\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 }
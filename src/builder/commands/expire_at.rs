@@ -1,5 +1,6 @@
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 use crate::types::ExpiryKind;
-use anyhow::anyhow;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 #[derive(Debug)]
 pub struct ExpireAt {}
@@ -11,21 +12,27 @@ impl ExpireAt {
     /// - key - The of the expiration
     /// - duration - The duration value in seconds
     /// - kind - The expiration kind variant.
-    pub fn build(key: &str, duration: &u64, kind: &Option<ExpiryKind>) -> anyhow::Result<String> {
+    pub fn build(key: &str, duration: &u64, kind: &Option<ExpiryKind>) -> crate::Result<Vec<u8>> {
         let duration =
             if let Some(time) = SystemTime::now().checked_add(Duration::from_secs(*duration)) {
-                time.duration_since(UNIX_EPOCH)?.as_secs()
+                time.duration_since(UNIX_EPOCH)
+                    .map_err(|_| Error::InvalidCommand("EXPIRE_AT_DURATION_INVALID".to_string()))?
+                    .as_secs()
             } else {
-                return Err(anyhow!("EXPIRE_AT_DURATION_INVALID"));
+                return Err(Error::InvalidCommand(
+                    "EXPIRE_AT_DURATION_INVALID".to_string(),
+                ));
             };
         if key.is_empty() {
-            return Err(anyhow!("EXPIRE_AT_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("EXPIRE_AT_KEY_REQUIRED".to_string()));
         }
+        let mut command = CommandEncoder::new("EXPIREAT")
+            .arg(key)
+            .arg(duration.to_string());
         if let Some(k) = kind {
-            Ok(format!("EXPIREAT {key} {duration} {k}\r\n"))
-        } else {
-            Ok(format!("EXPIREAT {key} {duration}\r\n"))
+            command = command.arg(k.to_string());
         }
+        Ok(command.build())
     }
 }
 
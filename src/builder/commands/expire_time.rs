@@ -1,15 +1,16 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug)]
 pub struct ExpireTime {}
 
 impl ExpireTime {
-    pub fn build(key: &str) -> anyhow::Result<String> {
+    pub fn build(key: &str) -> crate::Result<Vec<u8>> {
         if key.is_empty() {
-            return Err(anyhow!("EXPIRETIME_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("EXPIRETIME_KEY_REQUIRED".to_string()));
         }
 
-        Ok(format!("EXPIRETIME {key}\r\n"))
+        Ok(CommandEncoder::new("EXPIRETIME").arg(key).build())
     }
 }
 
@@ -21,6 +22,9 @@ pub mod test_expire_time {
     fn test() {
         let result = ExpireTime::build("mykey");
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("EXPIRETIME mykey\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*2\r\n$10\r\nEXPIRETIME\r\n$5\r\nmykey\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 }
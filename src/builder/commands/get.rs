@@ -1,14 +1,15 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug)]
 pub struct Get {}
 
 impl Get {
-    pub fn build(value: &str) -> anyhow::Result<String> {
+    pub fn build(value: &str) -> crate::Result<Vec<u8>> {
         if value.is_empty() {
-            return Err(anyhow!("GET_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("GET_KEY_REQUIRED".to_string()));
         }
-        Ok(format!("GET {value}\r\n"))
+        Ok(CommandEncoder::new("GET").arg(value).build())
     }
 }
 
@@ -20,7 +21,10 @@ pub mod test_get {
     fn test() {
         let result = Get::build("mykey");
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("GET mykey\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*2\r\n$3\r\nGET\r\n$5\r\nmykey\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 
     #[test]
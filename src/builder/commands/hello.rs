@@ -1,9 +1,55 @@
+use crate::Error;
+use crate::builder::commands::{AuthConfig, CommandEncoder};
+use crate::types::ProtocolVersion;
+
+#[derive(Debug, Clone, Default)]
+pub struct HelloConfig {
+    /// The protocol version to switch to. Required when `auth` or `client_name` is set.
+    pub protocol: Option<ProtocolVersion>,
+    /// Authenticates the connection. The `default` user is used when no username is given.
+    pub auth: Option<AuthConfig>,
+    /// Sets the connection name, the same as `CLIENT SETNAME`.
+    pub client_name: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct Hello {}
 
 impl Hello {
-    pub fn build() -> anyhow::Result<String> {
-        Ok(String::from("HELLO\r\n"))
+    /// Switches the protocol version and optionally authenticates and names the connection.
+    /// Reference: https://valkey.io/commands/hello/
+    /// Parameters:
+    /// - config - The protocol version, credentials and connection name.
+    pub fn build(config: &HelloConfig) -> crate::Result<Vec<u8>> {
+        let mut command = CommandEncoder::new("HELLO");
+        let protocol = match config.protocol {
+            Some(protocol) => protocol,
+            None if config.auth.is_some() || config.client_name.is_some() => {
+                return Err(Error::InvalidCommand("HELLO_PROTOCOL_REQUIRED".to_string()));
+            }
+            None => return Ok(command.build()),
+        };
+        command = command.arg(protocol.to_string());
+        if let Some(auth) = &config.auth {
+            let username = auth.username.as_deref().unwrap_or("default");
+            if username.is_empty() {
+                return Err(Error::InvalidCommand("HELLO_USERNAME_REQUIRED".to_string()));
+            }
+            let password = match &auth.password {
+                Some(password) if !password.is_empty() => password,
+                _ => return Err(Error::InvalidCommand("HELLO_PASSWORD_REQUIRED".to_string())),
+            };
+            command = command.arg("AUTH").arg(username).arg(password);
+        }
+        if let Some(client_name) = &config.client_name {
+            if client_name.is_empty() || client_name.contains(' ') {
+                return Err(Error::InvalidCommand(
+                    "HELLO_CLIENT_NAME_INVALID".to_string(),
+                ));
+            }
+            command = command.arg("SETNAME").arg(client_name);
+        }
+        Ok(command.build())
     }
 }
 
@@ -13,8 +59,81 @@ pub mod test_hello {
 
     #[test]
     fn test() {
-        let result = Hello::build();
+        let result = Hello::build(&HelloConfig::default());
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(b"*1\r\n$5\r\nHELLO\r\n".to_vec(), result.unwrap());
+    }
+
+    #[test]
+    fn test_protocol() {
+        let result = Hello::build(&HelloConfig {
+            protocol: Some(ProtocolVersion::Resp3),
+            ..Default::default()
+        });
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            b"*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n".to_vec(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_auth_client_name() {
+        let result = Hello::build(&HelloConfig {
+            protocol: Some(ProtocolVersion::Resp2),
+            auth: Some(AuthConfig {
+                username: Some("myapp".to_string()),
+                password: Some("mypassword".to_string()),
+            }),
+            client_name: Some("myclient".to_string()),
+        });
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("HELLO\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*7\r\n$5\r\nHELLO\r\n$1\r\n2\r\n$4\r\nAUTH\r\n$5\r\nmyapp\r\n$10\r\nmypassword\r\n$7\r\nSETNAME\r\n$8\r\nmyclient\r\n".to_vec(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_default_username() {
+        let result = Hello::build(&HelloConfig {
+            protocol: Some(ProtocolVersion::Resp3),
+            auth: Some(AuthConfig {
+                username: None,
+                password: Some("mypassword".to_string()),
+            }),
+            client_name: None,
+        });
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            b"*5\r\n$5\r\nHELLO\r\n$1\r\n3\r\n$4\r\nAUTH\r\n$7\r\ndefault\r\n$10\r\nmypassword\r\n"
+                .to_vec(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        let result = Hello::build(&HelloConfig {
+            protocol: None,
+            auth: None,
+            client_name: Some("myclient".to_string()),
+        });
+        assert!(result.is_err());
+        let result = Hello::build(&HelloConfig {
+            protocol: Some(ProtocolVersion::Resp3),
+            auth: Some(AuthConfig {
+                username: Some("myapp".to_string()),
+                password: None,
+            }),
+            client_name: None,
+        });
+        assert!(result.is_err());
+        let result = Hello::build(&HelloConfig {
+            protocol: Some(ProtocolVersion::Resp3),
+            auth: None,
+            client_name: Some("my client".to_string()),
+        });
+        assert!(result.is_err());
     }
 }
@@ -1,14 +1,15 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug)]
 pub struct Increment {}
 
 impl Increment {
-    pub fn build(value: &str) -> anyhow::Result<String> {
+    pub fn build(value: &str) -> crate::Result<Vec<u8>> {
         if value.is_empty() {
-            return Err(anyhow!("INCR_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("INCR_KEY_REQUIRED".to_string()));
         }
-        Ok(format!("INCR {value}\r\n"))
+        Ok(CommandEncoder::new("INCR").arg(value).build())
     }
 }
 
@@ -20,6 +21,9 @@ pub mod test_increment {
     fn test() {
         let result = Increment::build("mykey");
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("INCR mykey\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*2\r\n$4\r\nINCR\r\n$5\r\nmykey\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 }
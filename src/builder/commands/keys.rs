@@ -1,14 +1,15 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug)]
 pub struct Keys {}
 
 impl Keys {
-    pub fn build(key: &str) -> anyhow::Result<String> {
+    pub fn build(key: &str) -> crate::Result<Vec<u8>> {
         if key.is_empty() {
-            return Err(anyhow!("KEYS_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("KEYS_KEY_REQUIRED".to_string()));
         }
-        Ok(format!("KEYS {key}\r\n"))
+        Ok(CommandEncoder::new("KEYS").arg(key).build())
     }
 }
 
@@ -20,6 +21,6 @@ pub mod test_keys {
     fn test() {
         let result = Keys::build("*");
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("KEYS *\r\n".to_string(), result.unwrap());
+        assert_eq!(b"*2\r\n$4\r\nKEYS\r\n$1\r\n*\r\n".to_vec(), result.unwrap());
     }
 }
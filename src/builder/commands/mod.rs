@@ -1,7 +1,9 @@
 pub mod auth;
+pub mod custom;
 pub mod decrement;
 pub mod decrement_by;
 pub mod delete;
+pub mod encoder;
 pub mod expire;
 pub mod get;
 pub mod increment;
@@ -9,15 +11,19 @@ pub mod increment_by;
 pub mod keys;
 pub mod ping;
 pub mod raw;
+pub mod select;
 pub mod set;
 pub mod ttl;
 pub use auth::{Auth, AuthConfig};
+pub use custom::Custom;
 pub use delete::Delete;
+pub use encoder::CommandEncoder;
 pub use expire::Expire;
 pub use get::Get;
 pub use keys::Keys;
 pub use ping::Ping;
 pub use raw::Raw;
+pub use select::Select;
 pub use set::Set;
 pub use ttl::Ttl;
 pub mod exists;
@@ -29,6 +35,6 @@ pub use decrement_by::DecrementBy;
 pub use exists::Exists;
 pub use expire_at::ExpireAt;
 pub use expire_time::ExpireTime;
-pub use hello::Hello;
+pub use hello::{Hello, HelloConfig};
 pub use increment::Increment;
 pub use increment_by::IncrementBy;
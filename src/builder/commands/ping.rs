@@ -1,9 +1,11 @@
+use crate::builder::commands::CommandEncoder;
+
 #[derive(Debug)]
 pub struct Ping {}
 
 impl Ping {
-    pub fn build() -> anyhow::Result<String> {
-        Ok(String::from("PING\r\n"))
+    pub fn build() -> crate::Result<Vec<u8>> {
+        Ok(CommandEncoder::new("PING").build())
     }
 }
 
@@ -15,6 +17,6 @@ pub mod test_ping {
     fn test() {
         let result = Ping::build();
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("PING\r\n".to_string(), result.unwrap());
+        assert_eq!(b"*1\r\n$4\r\nPING\r\n".to_vec(), result.unwrap());
     }
 }
@@ -1,14 +1,15 @@
-use anyhow::anyhow;
+use crate::Error;
 
 #[derive(Debug)]
 pub struct Raw {}
 
 impl Raw {
-    pub fn build(value: &str) -> anyhow::Result<String> {
+    /// Sends the value as-is as an inline command, terminated by CRLF.
+    pub fn build(value: &str) -> crate::Result<Vec<u8>> {
         if value.is_empty() {
-            return Err(anyhow!("RAW_VALUE_REQUIRED"));
+            return Err(Error::InvalidCommand("RAW_VALUE_REQUIRED".to_string()));
         }
-        Ok(format!("{value}\r\n"))
+        Ok(format!("{value}\r\n").into_bytes())
     }
 }
 
@@ -21,7 +22,7 @@ pub mod test_get {
         let result = Raw::build("something that will produce an error");
         assert!(result.is_ok(), "{:#?}", result.err());
         assert_eq!(
-            "something that will produce an error\r\n".to_string(),
+            b"something that will produce an error\r\n".to_vec(),
             result.unwrap()
         );
     }
@@ -0,0 +1,27 @@
+use crate::builder::commands::CommandEncoder;
+
+#[derive(Debug)]
+pub struct Select {}
+
+impl Select {
+    pub fn build(database: &u32) -> crate::Result<Vec<u8>> {
+        Ok(CommandEncoder::new("SELECT")
+            .arg(database.to_string())
+            .build())
+    }
+}
+
+#[cfg(test)]
+pub mod test_select {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let result = Select::build(&2);
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            b"*2\r\n$6\r\nSELECT\r\n$1\r\n2\r\n".to_vec(),
+            result.unwrap()
+        );
+    }
+}
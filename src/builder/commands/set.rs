@@ -1,22 +1,31 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 use serde_json::Value;
 
 #[derive(Debug)]
 pub struct Set {}
 
 impl Set {
-    pub fn build(key: &str, value: &Value) -> anyhow::Result<String> {
+    pub fn build(key: &str, value: &Value) -> crate::Result<Vec<u8>> {
         if key.is_empty() {
-            return Err(anyhow!("SET_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("SET_KEY_REQUIRED".to_string()));
         }
         let value = match value.to_owned() {
             Value::String(value) => value,
             Value::Number(number) => number.to_string(),
             _ => {
-                return Err(anyhow!("SET_VALUE_NOT_SUPPORTED".to_string()));
+                return Err(Error::InvalidCommand("SET_VALUE_NOT_SUPPORTED".to_string()));
             }
         };
-        Ok(format!("SET {key} {value}\r\n"))
+        Ok(CommandEncoder::new("SET").arg(key).arg(value).build())
+    }
+
+    /// Builds a `SET` with a binary-safe value, sent without any conversion.
+    pub fn build_bytes(key: &str, value: &[u8]) -> crate::Result<Vec<u8>> {
+        if key.is_empty() {
+            return Err(Error::InvalidCommand("SET_KEY_REQUIRED".to_string()));
+        }
+        Ok(CommandEncoder::new("SET").arg(key).arg(value).build())
     }
 }
 
@@ -30,17 +39,37 @@ pub mod test_set {
     fn test_set_string() {
         let result = Set::build("mykey", &Value::String("mystringvalue".to_string()));
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("SET mykey mystringvalue\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*3\r\n$3\r\nSET\r\n$5\r\nmykey\r\n$13\r\nmystringvalue\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 
     #[test]
     fn test_set_number() {
         let result = Set::build("mykey", &Value::Number(Number::from_f64(1.00001).unwrap()));
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("SET mykey 1.00001\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*3\r\n$3\r\nSET\r\n$5\r\nmykey\r\n$7\r\n1.00001\r\n".to_vec(),
+            result.unwrap()
+        );
 
         let result = Set::build("mykey", &Value::Number(Number::from_u128(1).unwrap()));
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("SET mykey 1\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*3\r\n$3\r\nSET\r\n$5\r\nmykey\r\n$1\r\n1\r\n".to_vec(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_set_bytes() {
+        let result = Set::build_bytes("mykey", &[0x00, 0xFF, 0x0D, 0x0A]);
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            b"*3\r\n$3\r\nSET\r\n$5\r\nmykey\r\n$4\r\n\x00\xFF\r\n\r\n".to_vec(),
+            result.unwrap()
+        );
+        assert!(Set::build_bytes("", b"myvalue").is_err());
     }
 }
@@ -1,14 +1,15 @@
-use anyhow::anyhow;
+use crate::Error;
+use crate::builder::commands::CommandEncoder;
 
 #[derive(Debug)]
 pub struct Ttl {}
 
 impl Ttl {
-    pub fn build(value: &str) -> anyhow::Result<String> {
+    pub fn build(value: &str) -> crate::Result<Vec<u8>> {
         if value.is_empty() {
-            return Err(anyhow!("TTL_KEY_REQUIRED"));
+            return Err(Error::InvalidCommand("TTL_KEY_REQUIRED".to_string()));
         }
-        Ok(format!("TTL {value}\r\n"))
+        Ok(CommandEncoder::new("TTL").arg(value).build())
     }
 }
 
@@ -20,6 +21,9 @@ pub mod test_ttl {
     fn test() {
         let result = Ttl::build("mykey");
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("TTL mykey\r\n".to_string(), result.unwrap());
+        assert_eq!(
+            b"*2\r\n$3\r\nTTL\r\n$5\r\nmykey\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 }
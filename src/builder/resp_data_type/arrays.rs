@@ -1,105 +1,60 @@
-use crate::builder::resp_data_type::helpers::get_resp_multi_values;
-use crate::builder::resp_data_type::helpers::is_cr;
-use crate::builder::resp_data_type::helpers::is_lf;
+use crate::{Error, ProtocolCode};
+use crate::builder::resp_data_type::helpers::{
+    get_resp_multi_values, get_resp_value, get_streamed_values, is_streamed,
+};
 use crate::builder::resp_data_type::RespDataTypeTrait;
 use crate::builder::resp_data_type::RespParser;
-use crate::types::resp_data_kind::RespDataType;
-use crate::types::resp_data_type_iter::RespDataTypeIterator;
 use crate::types::RespDataTypeValue;
-use anyhow::anyhow;
 
 #[derive(Debug)]
 pub struct Arrays<'a> {
     value: &'a [u8],
     length: usize,
 }
-impl<'a> Arrays<'a> {
-    fn set_data(
-        &mut self,
-        parser: &mut RespParser,
-        data: &mut Vec<RespDataTypeValue>,
-        length: isize,
-    ) -> anyhow::Result<bool> {
-        let result = parser.parse()?;
-        data.push(result);
-        self.value = &self.value[parser.len()..];
-        Ok(length == data.len() as isize)
-    }
-}
+
 impl<'a> RespDataTypeTrait<'a> for Arrays<'a> {
     fn new(value: &'a [u8]) -> Self {
-        Self {
-            value,
-            length: value.len(),
-        }
+        Self { value, length: 0 }
     }
     fn len(&self) -> usize {
         self.length
     }
 
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
+        if is_streamed(self.value) {
+            // *?\r\n followed by the elements, until .\r\n
+            let (_, main_id) = get_resp_value(self.value, false)?;
+            if !main_id.is_arrays() {
+                return Err(Error::protocol(ProtocolCode::NotArraysType, 0));
+            }
+            let (data, end) = get_streamed_values(self.value)?;
+            self.length = end;
+            return Ok(RespDataTypeValue::Array(data));
+        }
         let (start, length, main_id) = get_resp_multi_values(self.value)?;
         if !main_id.is_arrays() {
-            return Err(anyhow!("NOT_ARRAYS_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotArraysType, 0));
+        }
+        if length <= -1 {
+            self.length = start;
+            return Ok(RespDataTypeValue::Null);
         }
         let mut data: Vec<RespDataTypeValue> = Vec::new();
-        if length > 0 {
-            self.value = &self.value[start..];
-            loop {
-                let id = if let Some(v) = self.value.first()
-                    && let Ok(kind) = RespDataType::identify(*v)
-                {
-                    kind
-                } else {
-                    return Err(anyhow!("INVALID_RESP_TYPE_ID"));
-                };
-                let mut iter = RespDataTypeIterator::new(self.value);
-                if id.is_arrays() {
-                    let result = self.build()?;
-                    data.push(result);
-                } else if id.is_bulk_strings() || id.is_verbatim_strings() || id.is_bulk_errors() {
-                    let _ = self.set_data(&mut RespParser::new(self.value), &mut data, length)?;
-                } else {
-                    let mut tmp_holder: Vec<u8> = Vec::new();
-                    while let Some(v) = iter.next() {
-                        tmp_holder.push(*v);
-                        if is_cr(v)
-                            && let Some(peek_values) = iter.npeek(2)
-                            && let Some(lf) = peek_values.first()
-                            && is_lf(lf)
-                            && let Some(next_id) = peek_values.get(1)
-                            && RespDataType::identify(*next_id).is_ok()
-                            && let Some(mut next_values) = iter.nnext(1)
-                        {
-                            tmp_holder.append(&mut next_values);
-                            let is_break = self.set_data(
-                                &mut RespParser::new(&tmp_holder),
-                                &mut data,
-                                length,
-                            )?;
-                            tmp_holder = vec![];
-                            if is_break {
-                                break;
-                            }
-                        }
-                    }
-
-                    if !tmp_holder.is_empty() {
-                        let _ =
-                            self.set_data(&mut RespParser::new(&tmp_holder), &mut data, length)?;
-                    }
-                }
-                if data.len() == length as usize {
-                    break;
-                }
-            }
+        let mut end = start;
+        while data.len() < length as usize {
+            let mut parser = RespParser::nested(self.value.get(end..).unwrap_or_default());
+            data.push(parser.parse().map_err(|err| err.shift(end))?);
+            end += parser.len();
         }
+        self.length = end;
         Ok(RespDataTypeValue::Array(data))
     }
 }
 
 #[cfg(test)]
 pub mod test_arrays {
+    use crate::types::resp_data_kind::RespDataType;
+
     use super::*;
 
     #[test]
@@ -221,6 +176,33 @@ pub mod test_arrays {
             let result = arrays.build();
             assert!(result.is_ok(), "{:#?}", result.err());
             assert_eq!(test_case.expected, result.unwrap());
+            assert_eq!(test_case.input.len(), arrays.len());
         }
     }
+
+    #[test]
+    fn test_null() {
+        // *-1\r\n
+        let input = vec![42, 45, 49, 13, 10];
+        let mut arrays = Arrays::new(&input);
+        let result = arrays.build();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(RespDataTypeValue::Null, result.unwrap());
+        assert_eq!(5, arrays.len());
+    }
+
+    #[test]
+    fn test_trailing_frame() {
+        // *1\r\n:1\r\n+OK\r\n
+        // Only the first frame belongs to the array.
+        let input = vec![42, 49, 13, 10, 58, 49, 13, 10, 43, 79, 75, 13, 10];
+        let mut arrays = Arrays::new(&input);
+        let result = arrays.build();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::Array(vec![RespDataTypeValue::Integer(1)]),
+            result.unwrap()
+        );
+        assert_eq!(8, arrays.len());
+    }
 }
@@ -0,0 +1,133 @@
+use crate::builder::resp_data_type::RespDataTypeTrait;
+use crate::builder::resp_data_type::RespParser;
+use crate::builder::resp_data_type::helpers::get_resp_multi_values;
+use crate::types::RespDataTypeValue;
+use crate::{Error, ProtocolCode};
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub struct Attributes<'a> {
+    value: &'a [u8],
+    length: usize,
+}
+
+impl<'a> Attributes<'a> {
+    fn next_value(&self, end: &mut usize) -> crate::Result<RespDataTypeValue> {
+        let mut parser = RespParser::nested(self.value.get(*end..).unwrap_or_default());
+        let result = parser.parse().map_err(|err| err.shift(*end))?;
+        *end += parser.len();
+        Ok(result)
+    }
+}
+
+impl<'a> RespDataTypeTrait<'a> for Attributes<'a> {
+    fn new(value: &'a [u8]) -> Self {
+        Self { value, length: 0 }
+    }
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Builds the attribute map together with the value it describes, which follows the map.
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
+        let (start, length, main_id) = get_resp_multi_values(self.value)?;
+        if !main_id.is_attributes() {
+            return Err(Error::protocol(ProtocolCode::NotAttributesType, 0));
+        }
+        let mut data: BTreeMap<RespDataTypeValue, RespDataTypeValue> = BTreeMap::new();
+        let mut end = start;
+        for _ in 0..length.max(0) {
+            let key = self.next_value(&mut end)?;
+            let value = self.next_value(&mut end)?;
+            data.insert(key, value);
+        }
+        let value = self.next_value(&mut end)?;
+        self.length = end;
+        Ok(RespDataTypeValue::Attributed(data, Box::new(value)))
+    }
+}
+
+#[cfg(test)]
+pub mod test_attributes {
+    use super::*;
+
+    #[test]
+    fn test_attributes() {
+        struct TestCase {
+            pub input: Vec<u8>,
+            pub expected: RespDataTypeValue,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                // |1\r\n+ttl\r\n:3600\r\n+OK\r\n
+                input: b"|1\r\n+ttl\r\n:3600\r\n+OK\r\n".to_vec(),
+                expected: RespDataTypeValue::Attributed(
+                    BTreeMap::from([(
+                        RespDataTypeValue::String("ttl".into()),
+                        RespDataTypeValue::Integer(3600),
+                    )]),
+                    Box::new(RespDataTypeValue::String("OK".into())),
+                ),
+            },
+            TestCase {
+                // |1\r\n+key-popularity\r\n%1\r\n$1\r\na\r\n,0.1923\r\n*1\r\n:2039\r\n
+                input: b"|1\r\n+key-popularity\r\n%1\r\n$1\r\na\r\n,0.1923\r\n*1\r\n:2039\r\n"
+                    .to_vec(),
+                expected: RespDataTypeValue::Attributed(
+                    BTreeMap::from([(
+                        RespDataTypeValue::String("key-popularity".into()),
+                        RespDataTypeValue::Object(BTreeMap::from([(
+                            RespDataTypeValue::String("a".into()),
+                            RespDataTypeValue::Double(0.1923.into()),
+                        )])),
+                    )]),
+                    Box::new(RespDataTypeValue::Array(vec![RespDataTypeValue::Integer(
+                        2039,
+                    )])),
+                ),
+            },
+        ];
+
+        for test_case in test_cases {
+            let mut attributes = Attributes::new(&test_case.input);
+            let result = attributes.build();
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(test_case.expected, result.unwrap());
+            assert_eq!(test_case.input.len(), attributes.len());
+        }
+    }
+
+    #[test]
+    fn test_nested_in_arrays() {
+        // *2\r\n|1\r\n+a\r\n:1\r\n:10\r\n:20\r\n
+        let input = b"*2\r\n|1\r\n+a\r\n:1\r\n:10\r\n:20\r\n".to_vec();
+        let mut parser = RespParser::new(&input);
+        let result = parser.parse();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let value = result.unwrap();
+        assert_eq!(
+            RespDataTypeValue::Array(vec![
+                RespDataTypeValue::Attributed(
+                    BTreeMap::from([(
+                        RespDataTypeValue::String("a".into()),
+                        RespDataTypeValue::Integer(1),
+                    )]),
+                    Box::new(RespDataTypeValue::Integer(10)),
+                ),
+                RespDataTypeValue::Integer(20),
+            ]),
+            value
+        );
+        assert_eq!(input.len(), parser.len());
+    }
+
+    #[test]
+    fn test_incomplete() {
+        // The attribute map is complete but the value it describes is missing.
+        let mut parser = RespParser::new(b"|1\r\n+ttl\r\n:3600\r\n");
+        let result = parser.try_parse();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(None, result.unwrap());
+    }
+}
@@ -1,8 +1,8 @@
+use crate::{Error, ProtocolCode};
 use crate::{
     builder::resp_data_type::{RespDataTypeTrait, helpers::get_resp_value},
     types::RespDataTypeValue,
 };
-use anyhow::anyhow;
 use num_bigint::BigInt;
 use regex::Regex;
 
@@ -19,20 +19,21 @@ impl<'a> RespDataTypeTrait<'a> for BigNumbers<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
         let (new_value, id) = get_resp_value(self.value, true)?;
         if !id.is_big_numbers() {
-            return Err(anyhow!("NOT_BIG_NUMBERS_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotBigNumbersType, 0));
         }
         self.length = new_value.len() + 3;
-        let pattern = Regex::new(r"^-?[0-9]+$")?;
+        let pattern = Regex::new(r"^-?[0-9]+$")
+            .map_err(|_| Error::protocol(ProtocolCode::BigNumbersInvalidValue, 1))?;
         if !pattern.is_match(String::from_utf8_lossy(new_value).as_ref()) {
-            return Err(anyhow!("BIG_NUMBERS_INVALID_VALUE".to_string()));
+            return Err(Error::protocol(ProtocolCode::BigNumbersInvalidValue, 1));
         }
         let parsed = if let Some(i) = BigInt::parse_bytes(new_value, 10) {
             i
         } else {
-            return Err(anyhow!("BIG_NUMBERS_PARSING_ERROR".to_string()));
+            return Err(Error::protocol(ProtocolCode::BigNumbersParsingError, 1));
         };
         Ok(RespDataTypeValue::BigNumber(parsed))
     }
@@ -1,8 +1,8 @@
+use crate::{Error, ProtocolCode};
 use crate::{
     builder::resp_data_type::{RespDataTypeTrait, helpers::get_resp_value},
     types::RespDataTypeValue,
 };
-use anyhow::anyhow;
 
 #[derive(Debug)]
 pub struct Booleans<'a> {
@@ -16,17 +16,17 @@ impl<'a> RespDataTypeTrait<'a> for Booleans<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
         let (new_value, id) = get_resp_value(self.value, true)?;
         if !id.is_booleans() {
-            return Err(anyhow!("NOT_BOOLEANS_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotBooleansType, 0));
         }
         self.length = new_value.len() + 3;
         let value = match String::from_utf8_lossy(new_value).to_string().as_str() {
             "t" => RespDataTypeValue::Boolean(true),
             "f" => RespDataTypeValue::Boolean(false),
             _ => {
-                return Err(anyhow!("BOOLEAN_INVALID_VALUE".to_string()));
+                return Err(Error::protocol(ProtocolCode::BooleanInvalidValue, 1));
             }
         };
         Ok(value)
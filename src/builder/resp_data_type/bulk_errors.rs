@@ -1,7 +1,7 @@
 use crate::builder::resp_data_type::RespDataTypeTrait;
 use crate::builder::resp_data_type::helpers::get_resp_multi_values;
 use crate::types::{RespDataTypeValue, RespErrorKind};
-use anyhow::anyhow;
+use crate::{Error, ProtocolCode};
 
 #[derive(Debug)]
 pub struct BulkErrors<'a> {
@@ -16,18 +16,23 @@ impl<'a> RespDataTypeTrait<'a> for BulkErrors<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
         let (start, length, id) = get_resp_multi_values(self.value)?;
         if !id.is_bulk_errors() {
-            return Err(anyhow!("NOT_BULK_ERRORS_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotBulkErrorsType, 0));
         }
         if length == 0 {
+            self.length = start + 2;
             return Ok(RespDataTypeValue::String("".to_string()));
         } else if length <= -1 {
+            self.length = start;
             return Ok(RespDataTypeValue::Null);
         }
         let end = start + length as usize;
-        let data = String::from_utf8_lossy(&self.value[start..end]).to_string();
+        let data = match self.value.get(start..end) {
+            Some(values) => String::from_utf8_lossy(values).to_string(),
+            None => return Err(Error::protocol(ProtocolCode::BulkErrorsLengthError, start)),
+        };
         let result = RespErrorKind::parse(data);
         self.length = end + 2;
         Ok(result)
@@ -1,7 +1,9 @@
 use crate::builder::resp_data_type::RespDataTypeTrait;
-use crate::builder::resp_data_type::helpers::get_resp_multi_values;
+use crate::builder::resp_data_type::helpers::{
+    get_resp_multi_values, get_resp_value, get_streamed_chunks, is_streamed,
+};
 use crate::types::RespDataTypeValue;
-use anyhow::anyhow;
+use crate::{Error, ProtocolCode};
 
 #[derive(Debug)]
 pub struct BulkStrings<'a> {
@@ -9,6 +11,32 @@ pub struct BulkStrings<'a> {
     length: usize,
 }
 
+impl<'a> BulkStrings<'a> {
+    /// Joins the chunks of a RESP3 streamed string, `$?` followed by `;<length>` chunks.
+    fn build_streamed(&mut self) -> crate::Result<Vec<u8>> {
+        let (_, id) = get_resp_value(self.value, false)?;
+        if !id.is_bulk_strings() {
+            return Err(Error::protocol(ProtocolCode::NotBulkStringsType, 0));
+        }
+        // The limits are checked by the parser before the string is built.
+        let (chunks, end) = match get_streamed_chunks(self.value, usize::MAX)? {
+            Some(result) => result,
+            None => {
+                return Err(Error::protocol(
+                    ProtocolCode::BulkStringsLengthError,
+                    self.value.len(),
+                ));
+            }
+        };
+        self.length = end;
+        Ok(chunks
+            .into_iter()
+            .flat_map(|chunk| &self.value[chunk])
+            .copied()
+            .collect())
+    }
+}
+
 impl<'a> RespDataTypeTrait<'a> for BulkStrings<'a> {
     fn new(value: &'a [u8]) -> Self {
         Self { value, length: 0 }
@@ -16,20 +44,31 @@ impl<'a> RespDataTypeTrait<'a> for BulkStrings<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
-        let (start, length, id) = get_resp_multi_values(self.value)?;
-        if !id.is_bulk_strings() {
-            return Err(anyhow!("NOT_BULK_STRINGS_TYPE"));
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
+        let data = if is_streamed(self.value) {
+            self.build_streamed()?
+        } else {
+            let (start, length, id) = get_resp_multi_values(self.value)?;
+            if !id.is_bulk_strings() {
+                return Err(Error::protocol(ProtocolCode::NotBulkStringsType, 0));
+            }
+            if length <= -1 {
+                self.length = start;
+                return Ok(RespDataTypeValue::Null);
+            }
+            let end = start + length as usize;
+            let data = match self.value.get(start..end) {
+                Some(values) => values.to_vec(),
+                None => return Err(Error::protocol(ProtocolCode::BulkStringsLengthError, start)),
+            };
+            self.length = end + 2;
+            data
+        };
+        // Binary payloads that are not valid UTF-8 are kept as-is.
+        match String::from_utf8(data) {
+            Ok(data) => Ok(RespDataTypeValue::String(data)),
+            Err(err) => Ok(RespDataTypeValue::Bytes(err.into_bytes())),
         }
-        if length == 0 {
-            return Ok(RespDataTypeValue::String("".to_string()));
-        } else if length <= -1 {
-            return Ok(RespDataTypeValue::Null);
-        }
-        let end = start + length as usize;
-        let data = String::from_utf8_lossy(&self.value[start..end]).to_string();
-        self.length = end + 2;
-        Ok(RespDataTypeValue::String(data))
     }
 }
 #[cfg(test)]
@@ -99,9 +138,12 @@ pub mod test_bulk_strings {
             TestCase {
                 // $4\r\n\x00\xFF\xAB\xCD\r\n
                 input: vec![identifier, 52, 13, 10, 0, 255, 171, 205, 13, 10],
-                expected: RespDataTypeValue::String(
-                    String::from_utf8_lossy(&[0x00, 0xFF, 0xAB, 0xCD]).to_string(),
-                ),
+                expected: RespDataTypeValue::Bytes(vec![0x00, 0xFF, 0xAB, 0xCD]),
+            },
+            TestCase {
+                // $5\r\n\xFF\r\n\xFE\x00\r\n
+                input: vec![identifier, 53, 13, 10, 255, 13, 10, 254, 0, 13, 10],
+                expected: RespDataTypeValue::Bytes(vec![0xFF, 0x0D, 0x0A, 0xFE, 0x00]),
             },
             TestCase {
                 // $10\r\n\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\r\n
@@ -0,0 +1,446 @@
+use crate::builder::resp_data_type::helpers::{
+    find_crlf, get_nested_frame_length, get_resp_multi_values, is_streamed, is_streamed_end,
+};
+use crate::builder::resp_data_type::{ParserLimits, RespParser};
+use crate::types::RespDataTypeValue;
+use crate::types::resp_data_kind::RespDataType;
+use crate::{Error, ProtocolCode};
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::Decoder;
+
+/// Incremental RESP decoder that can be fed partial input.
+/// It implements `tokio_util::codec::Decoder`, so it can be used with `FramedRead` or any
+/// transport that accumulates bytes in a `BytesMut`.
+/// `decode` remembers how far it checked an incomplete frame, so each byte of a large reply is
+/// walked once however many reads it takes to arrive.
+/// Between two calls the caller may only append bytes to the buffer, as `FramedRead` does.
+/// A buffer that was consumed or moved is walked again from its start, but one that was cleared
+/// and refilled in place can't be told apart, it needs a new decoder.
+#[derive(Debug, Clone, Default)]
+pub struct RespDecoder {
+    limits: ParserLimits,
+    scan: FrameScan,
+}
+
+/// The progress of the incomplete frame at the start of the buffer.
+#[derive(Debug, Clone, Default)]
+struct FrameScan {
+    /// The address of the first byte of the frame, to notice a buffer consumed by someone else.
+    start: usize,
+    /// The number of bytes of the frame checked so far.
+    end: usize,
+    /// The aggregates whose elements are still being received, the innermost last.
+    open: Vec<OpenAggregate>,
+}
+
+/// An aggregate whose elements are still being received.
+#[derive(Debug, Clone)]
+struct OpenAggregate {
+    /// The index of the aggregate header in the frame.
+    start: usize,
+    /// The number of elements left, `None` for a streamed aggregate ending with `.\r\n`.
+    remaining: Option<usize>,
+    /// The number of elements of an entry of a streamed aggregate, 2 for maps.
+    entry: usize,
+    /// The number of elements of a streamed aggregate received so far.
+    received: usize,
+}
+
+/// The next element of a frame.
+enum Element {
+    /// A value that is complete, with its length.
+    Value(usize),
+    /// The header of an aggregate, with its length.
+    Aggregate(usize, OpenAggregate),
+}
+
+impl RespDecoder {
+    /// Creates a decoder enforcing the default limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a decoder enforcing the given limits.
+    /// A frame exceeding them fails as soon as its header is received, so the caller never
+    /// buffers more than `max_frame_size` bytes for a single frame.
+    pub fn with_limits(limits: ParserLimits) -> Self {
+        Self {
+            limits,
+            scan: FrameScan::default(),
+        }
+    }
+
+    /// Decodes the first frame of the input.
+    /// Parameters:
+    /// - value - The bytes received so far.
+    ///   Returns:
+    /// - Some((value, length)) - The decoded value and the number of bytes it consumed.
+    /// - None - The frame is incomplete and more bytes are needed.
+    pub fn decode_slice(&self, value: &[u8]) -> crate::Result<Option<(RespDataTypeValue, usize)>> {
+        let mut parser = RespParser::with_limits(value, self.limits);
+        match parser.try_parse()? {
+            Some(result) => Ok(Some((result, parser.len()))),
+            None => Ok(None),
+        }
+    }
+
+    /// Walks the frame at the start of the input from where the previous call stopped.
+    /// Returns:
+    /// - Some(length) - The number of bytes the frame occupies.
+    /// - None - The frame is incomplete and more bytes are needed.
+    fn scan(&mut self, value: &[u8]) -> crate::Result<Option<usize>> {
+        let limits = &self.limits;
+        let scan = &mut self.scan;
+        loop {
+            match scan.open.last() {
+                Some(aggregate) if aggregate.remaining == Some(0) => {
+                    scan.open.pop();
+                    continue;
+                }
+                Some(aggregate)
+                    if aggregate.remaining.is_none()
+                        && aggregate.received % aggregate.entry == 0 =>
+                {
+                    match is_streamed_end(&value[scan.end..]).map_err(|err| err.shift(scan.end))? {
+                        Some(true) => {
+                            scan.end += 3;
+                            scan.open.pop();
+                            continue;
+                        }
+                        Some(false) => {}
+                        None => return Ok(None),
+                    }
+                    // The aggregate is nested in the ones opened before it.
+                    limits
+                        .check_aggregate(
+                            aggregate.received / aggregate.entry + 1,
+                            scan.open.len() - 1,
+                        )
+                        .map_err(|err| err.shift(aggregate.start))?;
+                }
+                Some(_) => {}
+                None if scan.end > 0 => return Ok(Some(scan.end)),
+                None => {}
+            }
+            let element = match scan_element(&value[scan.end..], limits, scan.open.len())
+                .map_err(|err| err.shift(scan.end))?
+            {
+                Some(element) => element,
+                None => return Ok(None),
+            };
+            if let Some(aggregate) = scan.open.last_mut() {
+                match aggregate.remaining.as_mut() {
+                    Some(remaining) => *remaining -= 1,
+                    None => aggregate.received += 1,
+                }
+            }
+            match element {
+                Element::Value(length) => scan.end += length,
+                Element::Aggregate(length, aggregate) => {
+                    scan.open.push(OpenAggregate {
+                        start: scan.end,
+                        ..aggregate
+                    });
+                    scan.end += length;
+                }
+            }
+        }
+    }
+}
+
+/// Checks the element at the start of the input, without walking the elements of an aggregate.
+/// Parameters:
+/// - value - The bytes of the frame from the start of the element.
+/// - limits - The bounds the frame must respect.
+/// - depth - The number of aggregates the element is nested in.
+fn scan_element(
+    value: &[u8],
+    limits: &ParserLimits,
+    depth: usize,
+) -> crate::Result<Option<Element>> {
+    let id = match value.first() {
+        Some(id) => RespDataType::identify(*id)?,
+        None => return Ok(None),
+    };
+    let header = match find_crlf(value, 1) {
+        Some(end) => end + 2,
+        None => return Ok(None),
+    };
+    let remaining = match id {
+        RespDataType::Arrays | RespDataType::Maps | RespDataType::Sets if is_streamed(value) => {
+            limits.check_aggregate(0, depth)?;
+            None
+        }
+        RespDataType::Arrays
+        | RespDataType::Maps
+        | RespDataType::Sets
+        | RespDataType::Pushes
+        | RespDataType::Attributes
+            if !is_streamed(value) =>
+        {
+            let (_, length, _) = get_resp_multi_values(value)?;
+            limits.check_aggregate(length.max(0) as usize, depth)?;
+            // Attributes are followed by the value they describe.
+            let count = match id {
+                RespDataType::Maps => length * 2,
+                RespDataType::Attributes => length.max(0) * 2 + 1,
+                _ => length,
+            };
+            Some(count.max(0) as usize)
+        }
+        _ => return Ok(get_nested_frame_length(value, limits, depth)?.map(Element::Value)),
+    };
+    let entry = if id.is_maps() { 2 } else { 1 };
+    Ok(Some(Element::Aggregate(
+        header,
+        OpenAggregate {
+            start: 0,
+            remaining,
+            entry,
+            received: 0,
+        },
+    )))
+}
+
+impl Decoder for RespDecoder {
+    type Item = RespDataTypeValue;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> crate::Result<Option<Self::Item>> {
+        // The buffer was consumed or moved by someone else, the frame has to be walked from its
+        // start.
+        let start = src.as_ptr() as usize;
+        if src.len() < self.scan.end || start != self.scan.start {
+            self.scan = FrameScan {
+                start,
+                ..Default::default()
+            };
+        }
+        let max_frame_size = self.limits.max_frame_size;
+        let length = match self.scan(src) {
+            Ok(Some(length)) if length > max_frame_size => Err(Error::protocol(
+                ProtocolCode::MaxFrameSizeExceeded,
+                max_frame_size,
+            )),
+            // The frame is still incomplete after the maximum size, it can only be larger.
+            Ok(None) if src.len() > max_frame_size => Err(Error::protocol(
+                ProtocolCode::MaxFrameSizeExceeded,
+                max_frame_size,
+            )),
+            Ok(None) => return Ok(None),
+            Ok(Some(length)) => Ok(length),
+            Err(err) => Err(err),
+        };
+        self.scan = FrameScan::default();
+        let length = length?;
+        // The frame was checked against the limits while it was walked.
+        let result = RespParser::nested(&src[..length]).parse()?;
+        src.advance(length);
+        Ok(Some(result))
+    }
+}
+
+#[cfg(test)]
+pub mod test_decoder {
+    use super::*;
+
+    #[test]
+    fn test_decode_slice() {
+        let decoder = RespDecoder::new();
+        let result = decoder.decode_slice(b"+OK\r\n:1\r\n");
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            Some((RespDataTypeValue::String("OK".to_string()), 5)),
+            result.unwrap()
+        );
+
+        let result = decoder.decode_slice(b"*2\r\n+OK\r\n");
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(None, result.unwrap());
+    }
+
+    #[test]
+    fn test_partial_input() {
+        // *2\r\n$5\r\nhello\r\n:42\r\n fed one byte at a time
+        let input = b"*2\r\n$5\r\nhello\r\n:42\r\n";
+        let mut decoder = RespDecoder::new();
+        let mut buffer = BytesMut::new();
+        for (i, b) in input.iter().enumerate() {
+            buffer.extend_from_slice(&[*b]);
+            let result = decoder.decode(&mut buffer);
+            assert!(result.is_ok(), "{:#?}", result.err());
+            let result = result.unwrap();
+            if i + 1 < input.len() {
+                assert_eq!(None, result);
+                assert_eq!(i + 1, buffer.len());
+            } else {
+                assert_eq!(
+                    Some(RespDataTypeValue::Array(vec![
+                        RespDataTypeValue::String("hello".to_string()),
+                        RespDataTypeValue::Integer(42),
+                    ])),
+                    result
+                );
+                assert!(buffer.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiple_frames() {
+        let mut decoder = RespDecoder::new();
+        let mut buffer = BytesMut::from(&b"+OK\r\n$-1\r\n#t\r\n:1"[..]);
+        let expected = vec![
+            RespDataTypeValue::String("OK".to_string()),
+            RespDataTypeValue::Null,
+            RespDataTypeValue::Boolean(true),
+        ];
+        for value in expected {
+            let result = decoder.decode(&mut buffer);
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(Some(value), result.unwrap());
+        }
+        let result = decoder.decode(&mut buffer);
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(None, result.unwrap());
+        assert_eq!(b":1".to_vec(), buffer.to_vec());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut decoder = RespDecoder::new();
+        let mut buffer = BytesMut::from(&b"?what\r\n"[..]);
+        assert!(decoder.decode(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_partial_nested() {
+        let inputs: Vec<&[u8]> = vec![
+            b"*2\r\n*2\r\n:1\r\n:2\r\n%1\r\n+a\r\n~2\r\n#t\r\n_\r\n",
+            b"*-1\r\n",
+            b"*0\r\n",
+            b"*?\r\n:1\r\n*?\r\n.\r\n$?\r\n;2\r\nab\r\n;0\r\n.\r\n",
+            b"%?\r\n+a\r\n:1\r\n+b\r\n*1\r\n:2\r\n.\r\n",
+            b"|1\r\n+ttl\r\n:3600\r\n*1\r\n=7\r\ntxt:abc\r\n",
+            b">2\r\n+message\r\n$5\r\nhello\r\n",
+        ];
+        for input in inputs {
+            let expected = RespParser::new(input).parse();
+            assert!(expected.is_ok(), "{:#?}", expected.err());
+            let mut decoder = RespDecoder::new();
+            let mut buffer = BytesMut::new();
+            let mut result = None;
+            for b in input.iter() {
+                assert_eq!(None, result, "{:?}", input);
+                buffer.extend_from_slice(&[*b]);
+                let decoded = decoder.decode(&mut buffer);
+                assert!(decoded.is_ok(), "{:#?}", decoded.err());
+                result = decoded.unwrap();
+            }
+            assert_eq!(Some(expected.unwrap()), result, "{:?}", input);
+            assert!(buffer.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_large_array() {
+        // 100000 bulk strings received in reads of 4 KB, each byte is walked once.
+        let length = 100_000;
+        let mut input = format!("*{length}\r\n").into_bytes();
+        for i in 0..length {
+            let value = format!("value-{i}");
+            input.extend_from_slice(format!("${}\r\n{value}\r\n", value.len()).as_bytes());
+        }
+        input.extend_from_slice(b"+OK\r\n");
+        let mut decoder = RespDecoder::new();
+        let mut buffer = BytesMut::new();
+        let mut values = Vec::new();
+        for chunk in input.chunks(4096) {
+            buffer.extend_from_slice(chunk);
+            loop {
+                let result = decoder.decode(&mut buffer);
+                assert!(result.is_ok(), "{:#?}", result.err());
+                match result.unwrap() {
+                    Some(value) => values.push(value),
+                    None => break,
+                }
+            }
+        }
+        assert_eq!(2, values.len());
+        match &values[0] {
+            RespDataTypeValue::Array(elements) => {
+                assert_eq!(length, elements.len());
+                assert_eq!(
+                    RespDataTypeValue::String(format!("value-{}", length - 1)),
+                    elements[length - 1]
+                );
+            }
+            value => panic!("{value:?}"),
+        }
+        assert_eq!(RespDataTypeValue::String("OK".to_string()), values[1]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_consumed_by_caller() {
+        let mut decoder = RespDecoder::new();
+        let mut buffer = BytesMut::from(&b"*3\r\n:1\r\n"[..]);
+        let result = decoder.decode(&mut buffer);
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(None, result.unwrap());
+        // The caller drops the incomplete frame and receives a longer one.
+        buffer.advance(buffer.len());
+        buffer.extend_from_slice(b"+OK\r\n:12345\r\n");
+        let result = decoder.decode(&mut buffer);
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            Some(RespDataTypeValue::String("OK".to_string())),
+            result.unwrap()
+        );
+        assert_eq!(b":12345\r\n".to_vec(), buffer.to_vec());
+    }
+
+    #[test]
+    fn test_partial_limits() {
+        let limits = ParserLimits {
+            max_depth: 2,
+            max_bulk_length: 5,
+            max_aggregate_length: 3,
+            max_frame_size: 64,
+        };
+        let oversized = [b"*1\r\n+".to_vec(), vec![b'a'; 64]].concat();
+        let inputs: Vec<&[u8]> = vec![
+            b"*2\r\n:1\r\n$6\r\nhello!\r\n",
+            b"*1\r\n*1\r\n*1\r\n:1\r\n",
+            b"*1\r\n*?\r\n:1\r\n:2\r\n:3\r\n:4\r\n.\r\n",
+            b"*1\r\n$?\r\n;4\r\nHell\r\n;2\r\no!\r\n;0\r\n",
+            b"$?\r\n;18446744073709551615\r\n",
+            b"%?\r\n+a\r\n:1\r\n+b\r\n:2\r\n+c\r\n:3\r\n+d\r\n:4\r\n.\r\n",
+            b"*1\r\n|1\r\n+a\r\n:1\r\n*0\r\n",
+            b"*2\r\n%4\r\n",
+            // The frame is incomplete but already larger than the maximum size.
+            &oversized,
+        ];
+        for input in inputs {
+            // The error and its offset are the same as when the whole frame is checked at once.
+            let expected = RespDecoder::with_limits(limits).decode_slice(input);
+            assert!(expected.is_err(), "{:?}", input);
+            let mut decoder = RespDecoder::with_limits(limits);
+            let mut buffer = BytesMut::new();
+            let mut result = Ok(None);
+            for b in input.iter() {
+                buffer.extend_from_slice(&[*b]);
+                result = decoder.decode(&mut buffer);
+                if result.is_err() {
+                    break;
+                }
+            }
+            assert_eq!(
+                format!("{expected:?}"),
+                format!("{result:?}"),
+                "{:?}",
+                input
+            );
+        }
+    }
+}
@@ -1,8 +1,8 @@
+use crate::{Error, ProtocolCode};
 use crate::{
     builder::resp_data_type::{helpers::get_resp_value, RespDataTypeTrait},
     types::RespDataTypeValue,
 };
-use anyhow::anyhow;
 use ordered_float::OrderedFloat;
 
 #[derive(Debug)]
@@ -17,14 +17,16 @@ impl<'a> RespDataTypeTrait<'a> for Doubles<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
         let (new_value, id) = get_resp_value(self.value, true)?;
         if !id.is_doubles() {
-            return Err(anyhow!("NOT_DOUBLES_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotDoublesType, 0));
         }
         self.length = new_value.len() + 3;
         let value = String::from_utf8_lossy(new_value).to_string();
-        let parsed = value.parse::<f64>()?;
+        let parsed = value
+            .parse::<f64>()
+            .map_err(|_| Error::protocol(ProtocolCode::DoublesInvalidValue, 1))?;
         let result = if parsed.is_nan() {
             RespDataTypeValue::Nan
         } else if parsed.is_infinite() && parsed.is_sign_positive() {
@@ -78,6 +80,7 @@ pub mod test_doubles {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_finites() {
         struct TestCase {
             pub input: Vec<u8>,
@@ -0,0 +1,309 @@
+use crate::Error;
+use crate::types::{ProtocolVersion, RespDataTypeValue};
+use std::collections::BTreeMap;
+
+/// Encodes values back to RESP bytes, e.g. to write fake servers, proxies or golden files.
+/// RESP2 has no maps, sets, pushes, doubles, booleans, big numbers, verbatim strings or attributes,
+/// so in RESP2 mode they are sent the way a server does over RESP2: aggregates become (flat) arrays,
+/// booleans become integers, numbers and verbatim strings become bulk strings and attributes are dropped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RespEncoder {
+    protocol: ProtocolVersion,
+}
+
+impl RespEncoder {
+    pub fn new(protocol: ProtocolVersion) -> Self {
+        Self { protocol }
+    }
+
+    /// Encodes a single value.
+    pub fn encode(&self, value: &RespDataTypeValue) -> crate::Result<Vec<u8>> {
+        let mut data: Vec<u8> = Vec::new();
+        self.encode_into(value, &mut data)?;
+        Ok(data)
+    }
+
+    /// Appends the encoded value to the buffer.
+    pub fn encode_into(&self, value: &RespDataTypeValue, data: &mut Vec<u8>) -> crate::Result<()> {
+        let is_resp3 = self.protocol == ProtocolVersion::Resp3;
+        match value {
+            RespDataTypeValue::String(value) => {
+                if value.contains(['\r', '\n']) {
+                    Self::bulk(b'$', value.as_bytes(), data);
+                } else {
+                    Self::line(b'+', value, data);
+                }
+            }
+            RespDataTypeValue::Bytes(value) => Self::bulk(b'$', value, data),
+            RespDataTypeValue::VerbatimString(value, encoding) if is_resp3 => {
+                Self::bulk(b'=', format!("{encoding}:{value}").as_bytes(), data)
+            }
+            RespDataTypeValue::VerbatimString(value, _) => Self::bulk(b'$', value.as_bytes(), data),
+            RespDataTypeValue::VerbatimBytes(value, encoding) if is_resp3 => {
+                let mut payload = format!("{encoding}:").into_bytes();
+                payload.extend_from_slice(value);
+                Self::bulk(b'=', &payload, data)
+            }
+            RespDataTypeValue::VerbatimBytes(value, _) => Self::bulk(b'$', value, data),
+            RespDataTypeValue::Error(kind, message) => {
+                let value = match kind.prefix() {
+                    "" => message.to_owned(),
+                    prefix => format!("{prefix} {message}"),
+                };
+                if !value.contains(['\r', '\n']) {
+                    Self::line(b'-', &value, data);
+                } else if is_resp3 {
+                    Self::bulk(b'!', value.as_bytes(), data);
+                } else {
+                    return Err(Error::Encode("ENCODER_ERROR_MESSAGE_INVALID".to_string()));
+                }
+            }
+            RespDataTypeValue::Boolean(value) if is_resp3 => {
+                Self::line(b'#', if *value { "t" } else { "f" }, data)
+            }
+            RespDataTypeValue::Boolean(value) => {
+                Self::line(b':', if *value { "1" } else { "0" }, data)
+            }
+            RespDataTypeValue::Integer(value) => Self::line(b':', &value.to_string(), data),
+            RespDataTypeValue::Double(value) => self.number(&value.to_string(), data),
+            RespDataTypeValue::Infinity => self.number("inf", data),
+            RespDataTypeValue::NegativeInfinity => self.number("-inf", data),
+            RespDataTypeValue::Nan => self.number("nan", data),
+            RespDataTypeValue::BigNumber(value) if is_resp3 => {
+                Self::line(b'(', &value.to_string(), data)
+            }
+            RespDataTypeValue::BigNumber(value) => {
+                Self::bulk(b'$', value.to_string().as_bytes(), data)
+            }
+            RespDataTypeValue::Null if is_resp3 => data.extend_from_slice(b"_\r\n"),
+            RespDataTypeValue::Null => data.extend_from_slice(b"$-1\r\n"),
+            RespDataTypeValue::Array(values) => {
+                self.aggregate(b'*', values.len(), values.iter(), data)?
+            }
+            RespDataTypeValue::Set(values) => self.aggregate(
+                if is_resp3 { b'~' } else { b'*' },
+                values.len(),
+                values.iter(),
+                data,
+            )?,
+            RespDataTypeValue::Push(values) => self.aggregate(
+                if is_resp3 { b'>' } else { b'*' },
+                values.len(),
+                values.iter(),
+                data,
+            )?,
+            RespDataTypeValue::Object(values) if is_resp3 => self.map(b'%', values, data)?,
+            RespDataTypeValue::Object(values) => {
+                let length = values.len() * 2;
+                let values = values.iter().flat_map(|(key, value)| [key, value]);
+                self.aggregate(b'*', length, values, data)?
+            }
+            RespDataTypeValue::Attributed(attributes, value) => {
+                if is_resp3 {
+                    self.map(b'|', attributes, data)?;
+                }
+                self.encode_into(value, data)?
+            }
+        }
+        Ok(())
+    }
+
+    fn line(id: u8, value: &str, data: &mut Vec<u8>) {
+        data.push(id);
+        data.extend_from_slice(value.as_bytes());
+        data.extend_from_slice(b"\r\n");
+    }
+
+    fn bulk(id: u8, value: &[u8], data: &mut Vec<u8>) {
+        Self::line(id, &value.len().to_string(), data);
+        data.extend_from_slice(value);
+        data.extend_from_slice(b"\r\n");
+    }
+
+    fn number(&self, value: &str, data: &mut Vec<u8>) {
+        match self.protocol {
+            ProtocolVersion::Resp3 => Self::line(b',', value, data),
+            ProtocolVersion::Resp2 => Self::bulk(b'$', value.as_bytes(), data),
+        }
+    }
+
+    fn aggregate<'v>(
+        &self,
+        id: u8,
+        length: usize,
+        values: impl Iterator<Item = &'v RespDataTypeValue>,
+        data: &mut Vec<u8>,
+    ) -> crate::Result<()> {
+        Self::line(id, &length.to_string(), data);
+        for value in values {
+            self.encode_into(value, data)?;
+        }
+        Ok(())
+    }
+
+    fn map(
+        &self,
+        id: u8,
+        values: &BTreeMap<RespDataTypeValue, RespDataTypeValue>,
+        data: &mut Vec<u8>,
+    ) -> crate::Result<()> {
+        Self::line(id, &values.len().to_string(), data);
+        for (key, value) in values {
+            self.encode_into(key, data)?;
+            self.encode_into(value, data)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod test_encoder {
+    use super::*;
+    use crate::builder::resp_data_type::RespParser;
+    use crate::types::{RespErrorKind, VerbatimEncoding};
+    use num_bigint::BigInt;
+    use ordered_float::OrderedFloat;
+    use std::collections::BTreeSet;
+
+    fn string(value: &str) -> RespDataTypeValue {
+        RespDataTypeValue::String(value.to_string())
+    }
+
+    #[test]
+    fn test_encode() {
+        struct TestCase {
+            pub input: RespDataTypeValue,
+            pub resp2: Vec<u8>,
+            pub resp3: Vec<u8>,
+        }
+        let test_cases = vec![
+            TestCase {
+                input: string("OK"),
+                resp2: b"+OK\r\n".to_vec(),
+                resp3: b"+OK\r\n".to_vec(),
+            },
+            TestCase {
+                input: string("a\r\nb"),
+                resp2: b"$4\r\na\r\nb\r\n".to_vec(),
+                resp3: b"$4\r\na\r\nb\r\n".to_vec(),
+            },
+            TestCase {
+                input: RespDataTypeValue::Error(RespErrorKind::WrongType, "wrong kind".to_string()),
+                resp2: b"-WRONGTYPE wrong kind\r\n".to_vec(),
+                resp3: b"-WRONGTYPE wrong kind\r\n".to_vec(),
+            },
+            TestCase {
+                input: RespDataTypeValue::Boolean(true),
+                resp2: b":1\r\n".to_vec(),
+                resp3: b"#t\r\n".to_vec(),
+            },
+            TestCase {
+                input: RespDataTypeValue::Double(OrderedFloat(1.5)),
+                resp2: b"$3\r\n1.5\r\n".to_vec(),
+                resp3: b",1.5\r\n".to_vec(),
+            },
+            TestCase {
+                input: RespDataTypeValue::NegativeInfinity,
+                resp2: b"$4\r\n-inf\r\n".to_vec(),
+                resp3: b",-inf\r\n".to_vec(),
+            },
+            TestCase {
+                input: RespDataTypeValue::BigNumber(BigInt::from(-12345)),
+                resp2: b"$6\r\n-12345\r\n".to_vec(),
+                resp3: b"(-12345\r\n".to_vec(),
+            },
+            TestCase {
+                input: RespDataTypeValue::VerbatimString("hi".to_string(), VerbatimEncoding::Txt),
+                resp2: b"$2\r\nhi\r\n".to_vec(),
+                resp3: b"=6\r\ntxt:hi\r\n".to_vec(),
+            },
+            TestCase {
+                input: RespDataTypeValue::Null,
+                resp2: b"$-1\r\n".to_vec(),
+                resp3: b"_\r\n".to_vec(),
+            },
+            TestCase {
+                input: RespDataTypeValue::Object(BTreeMap::from([(
+                    string("a"),
+                    RespDataTypeValue::Integer(1),
+                )])),
+                resp2: b"*2\r\n+a\r\n:1\r\n".to_vec(),
+                resp3: b"%1\r\n+a\r\n:1\r\n".to_vec(),
+            },
+            TestCase {
+                input: RespDataTypeValue::Attributed(
+                    BTreeMap::from([(string("ttl"), RespDataTypeValue::Integer(1))]),
+                    Box::new(string("OK")),
+                ),
+                resp2: b"+OK\r\n".to_vec(),
+                resp3: b"|1\r\n+ttl\r\n:1\r\n+OK\r\n".to_vec(),
+            },
+        ];
+        for test_case in test_cases {
+            let result = RespEncoder::new(ProtocolVersion::Resp2).encode(&test_case.input);
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(test_case.resp2, result.unwrap(), "{:?}", test_case.input);
+            let result = RespEncoder::new(ProtocolVersion::Resp3).encode(&test_case.input);
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(test_case.resp3, result.unwrap(), "{:?}", test_case.input);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let values = vec![
+            string("hello"),
+            string("line1\r\nline2"),
+            RespDataTypeValue::Bytes(vec![0x00, 0xFF, 0x0D, 0x0A]),
+            RespDataTypeValue::Error(RespErrorKind::Err, "unknown command".to_string()),
+            RespDataTypeValue::Error(RespErrorKind::Syntax, "line1\r\nline2".to_string()),
+            RespDataTypeValue::Integer(-42),
+            RespDataTypeValue::Boolean(false),
+            RespDataTypeValue::Double(OrderedFloat(3.25)),
+            RespDataTypeValue::Infinity,
+            RespDataTypeValue::Nan,
+            RespDataTypeValue::BigNumber(BigInt::from(i128::MAX)),
+            RespDataTypeValue::VerbatimString("## title".to_string(), VerbatimEncoding::Mkd),
+            RespDataTypeValue::VerbatimBytes(vec![0xFF, 0x00], VerbatimEncoding::Txt),
+            RespDataTypeValue::Null,
+            RespDataTypeValue::Array(vec![
+                RespDataTypeValue::Integer(1),
+                RespDataTypeValue::Array(vec![]),
+                RespDataTypeValue::Null,
+            ]),
+            RespDataTypeValue::Object(BTreeMap::from([
+                (string("first"), RespDataTypeValue::Integer(1)),
+                (
+                    string("second"),
+                    RespDataTypeValue::Set(BTreeSet::from([string("a"), string("b")])),
+                ),
+            ])),
+            RespDataTypeValue::Push(vec![string("message"), string("hello")]),
+            RespDataTypeValue::Attributed(
+                BTreeMap::from([(string("ttl"), RespDataTypeValue::Integer(3600))]),
+                Box::new(RespDataTypeValue::Integer(1)),
+            ),
+        ];
+        let encoder = RespEncoder::new(ProtocolVersion::Resp3);
+        for value in values {
+            let result = encoder.encode(&value);
+            assert!(result.is_ok(), "{:#?}", result.err());
+            let data = result.unwrap();
+            let mut parser = RespParser::new(&data);
+            let result = parser.parse();
+            assert!(result.is_ok(), "{:?}: {:#?}", value, result.err());
+            assert_eq!(value, result.unwrap());
+            assert_eq!(data.len(), parser.len());
+        }
+    }
+
+    #[test]
+    fn test_error() {
+        let value = RespDataTypeValue::Error(RespErrorKind::Err, "a\r\nb".to_string());
+        assert!(
+            RespEncoder::new(ProtocolVersion::Resp2)
+                .encode(&value)
+                .is_err()
+        );
+    }
+}
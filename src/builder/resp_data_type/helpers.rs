@@ -1,27 +1,35 @@
-use anyhow::anyhow;
+use crate::builder::resp_data_type::{ParserLimits, RespParser};
+use crate::types::RespDataTypeValue;
+use crate::{Error, ProtocolCode};
+use std::ops::Range;
 
 use crate::types::{resp_data_kind::RespDataType, resp_data_type_iter::RespDataTypeIterator};
 
 /// Get the resp data type, value part, and remove the terminator (CR,LF).
 /// Parameters:
 /// - value - The array unsigned integer representation of the input.
-/// - remove_terminator - If true, remove the terminator.
+/// - remove_terminator - If true, the value ends at the first terminator and the terminator is removed.
 ///   Returns:
 /// - value - The array unsigned integer value.
 /// - id - The resp data type.
 pub fn get_resp_value(
     value: &[u8],
     remove_terminator: bool,
-) -> anyhow::Result<(&[u8], RespDataType)> {
+) -> crate::Result<(&[u8], RespDataType)> {
     let id = if let Some(id) = value.first() {
         RespDataType::identify(*id)?
     } else {
-        return Err(anyhow!("INVALID_RESP_DATA_TYPE"));
+        return Err(Error::protocol(ProtocolCode::InvalidRespDataType, 0));
     };
-    let crlf = if remove_terminator { 2 } else { 0 };
-    match value.get(1..value.len() - crlf) {
-        Some(data) => Ok((data, id)),
-        None => Err(anyhow!("INVALID_CRLF_TERMINATOR")),
+    if !remove_terminator {
+        return Ok((&value[1..], id));
+    }
+    match find_crlf(value, 1) {
+        Some(end) => Ok((&value[1..end], id)),
+        None => Err(Error::protocol(
+            ProtocolCode::InvalidCrlfTerminator,
+            value.len(),
+        )),
     }
 }
 
@@ -32,13 +40,13 @@ pub fn get_resp_value(
 /// - new_start_index - The index where the value will start after getting the length
 /// - length - The length of the value.
 /// - id - The resp data type.
-pub fn get_resp_multi_values(value: &[u8]) -> anyhow::Result<(usize, isize, RespDataType)> {
+pub fn get_resp_multi_values(value: &[u8]) -> crate::Result<(usize, isize, RespDataType)> {
     let mut length: Vec<u8> = Vec::new();
     let mut iter = RespDataTypeIterator::new(value);
     let id = if let Some(id) = iter.next() {
         RespDataType::identify(*id)?
     } else {
-        return Err(anyhow!("INVALID_RESP_DATA_TYPE"));
+        return Err(Error::protocol(ProtocolCode::InvalidRespDataType, 0));
     };
     while let Some(v) = iter.next() {
         length.push(*v);
@@ -55,7 +63,7 @@ pub fn get_resp_multi_values(value: &[u8]) -> anyhow::Result<(usize, isize, Resp
     let length = if let Ok(n) = String::from_utf8_lossy(&length).parse::<isize>() {
         n
     } else {
-        return Err(anyhow!("INVALID_LENGTH".to_string()));
+        return Err(Error::protocol(ProtocolCode::InvalidLength, 1));
     };
     Ok((start, length, id))
 }
@@ -73,3 +81,249 @@ pub fn is_cr(value: &u8) -> bool {
 pub fn is_lf(value: &u8) -> bool {
     value == &10
 }
+
+/// Find the index of the first terminator (CR,LF).
+/// Parameters:
+/// - value - The array unsigned integer representation of the input.
+/// - from - The index where the search starts.
+pub fn find_crlf(value: &[u8], from: usize) -> Option<usize> {
+    value
+        .get(from..)?
+        .windows(2)
+        .position(|w| is_cr(&w[0]) && is_lf(&w[1]))
+        .map(|i| i + from)
+}
+
+/// Checks if the value is a RESP3 streamed string or aggregate, its length is `?` (e.g. `$?\r\n`).
+/// Parameters:
+/// - value - The array unsigned integer representation of the input.
+pub fn is_streamed(value: &[u8]) -> bool {
+    value.get(1..4) == Some(b"?\r\n")
+}
+
+/// Get the chunks of a RESP3 streamed string, e.g. `$?\r\n;4\r\nHell\r\n;1\r\no\r\n;0\r\n`.
+/// The chunk lengths are added up as they are read, so a string longer than the maximum fails
+/// before its next chunk is received, and a huge declared length can't overflow the offsets.
+/// Parameters:
+/// - value - The array unsigned integer representation of the input, starting with `$?\r\n`.
+/// - max_length - The maximum length of the whole string, e.g. `ParserLimits::max_bulk_length`.
+///   Returns:
+/// - Some((chunks, end)) - The position of each chunk payload, and the index right after the `;0` chunk.
+/// - None - The string is incomplete and more bytes are needed.
+pub fn get_streamed_chunks(
+    value: &[u8],
+    max_length: usize,
+) -> crate::Result<Option<(Vec<Range<usize>>, usize)>> {
+    let mut chunks: Vec<Range<usize>> = Vec::new();
+    let mut total: usize = 0;
+    let mut start = 4;
+    loop {
+        match value.get(start) {
+            Some(b';') => {}
+            Some(_) => {
+                return Err(Error::protocol(
+                    ProtocolCode::StreamedStringChunkInvalid,
+                    start,
+                ));
+            }
+            None => return Ok(None),
+        }
+        let header = match find_crlf(value, start + 1) {
+            Some(end) => end,
+            None => return Ok(None),
+        };
+        let length = match std::str::from_utf8(&value[start + 1..header])
+            .ok()
+            .and_then(|length| length.parse::<usize>().ok())
+        {
+            Some(length) => length,
+            None => return Err(Error::protocol(ProtocolCode::InvalidLength, start + 1)),
+        };
+        // The chunk of length zero ends the string.
+        if length == 0 {
+            return Ok(Some((chunks, header + 2)));
+        }
+        total = match total.checked_add(length) {
+            Some(total) if total <= max_length => total,
+            _ => return Err(Error::protocol(ProtocolCode::MaxBulkLengthExceeded, 1)),
+        };
+        let end = match (header + 2).checked_add(length) {
+            Some(end) if end.checked_add(2).is_some() => end,
+            _ => return Err(Error::protocol(ProtocolCode::InvalidLength, start + 1)),
+        };
+        match value.get(end..end + 2) {
+            Some(crlf) if is_cr(&crlf[0]) && is_lf(&crlf[1]) => {}
+            Some(_) => return Err(Error::protocol(ProtocolCode::InvalidCrlfTerminator, end)),
+            None => return Ok(None),
+        }
+        chunks.push(header + 2..end);
+        start = end + 2;
+    }
+}
+
+/// Checks if a RESP3 streamed aggregate ends at the start of the value.
+/// Parameters:
+/// - value - The array unsigned integer representation of the input.
+///   Returns:
+/// - Some(true) - The value starts with the end marker `.\r\n`.
+/// - Some(false) - The value starts with the next element.
+/// - None - The end marker is incomplete and more bytes are needed.
+pub fn is_streamed_end(value: &[u8]) -> crate::Result<Option<bool>> {
+    match value.first() {
+        Some(b'.') => match value.get(1..3) {
+            Some(crlf) if is_cr(&crlf[0]) && is_lf(&crlf[1]) => Ok(Some(true)),
+            Some(_) => Err(Error::protocol(ProtocolCode::StreamedEndInvalid, 1)),
+            None => Ok(None),
+        },
+        Some(_) => Ok(Some(false)),
+        None => Ok(None),
+    }
+}
+
+/// Parses the elements of a complete RESP3 streamed aggregate, e.g. `*?\r\n:1\r\n:2\r\n.\r\n`.
+/// Parameters:
+/// - value - The array unsigned integer representation of the input, starting with the header (e.g. `*?\r\n`).
+///   Returns:
+/// - values - The elements, keys and values alternate for maps.
+/// - end - The index right after the end marker.
+pub fn get_streamed_values(value: &[u8]) -> crate::Result<(Vec<RespDataTypeValue>, usize)> {
+    let mut data: Vec<RespDataTypeValue> = Vec::new();
+    let mut end = 4;
+    loop {
+        match is_streamed_end(value.get(end..).unwrap_or_default()) {
+            Ok(Some(true)) => return Ok((data, end + 3)),
+            Ok(Some(false)) => {}
+            Ok(None) => return Err(Error::protocol(ProtocolCode::StreamedEndNotFound, end)),
+            Err(err) => return Err(err.shift(end)),
+        }
+        let mut parser = RespParser::nested(&value[end..]);
+        data.push(parser.parse().map_err(|err| err.shift(end))?);
+        end += parser.len();
+    }
+}
+
+/// Get the length of the first complete frame of the input, including the nested values of aggregates.
+/// The frame is checked against the limits while it is walked, so no nested value is parsed
+/// when a header exceeds them.
+/// Parameters:
+/// - value - The array unsigned integer representation of the input.
+/// - limits - The bounds the frame must respect.
+///   Returns:
+/// - Some(length) - The number of bytes the frame occupies.
+/// - None - The frame is incomplete and more bytes are needed.
+pub fn get_frame_length(value: &[u8], limits: &ParserLimits) -> crate::Result<Option<usize>> {
+    match get_nested_frame_length(value, limits, 0)? {
+        Some(length) if length > limits.max_frame_size => Err(Error::protocol(
+            ProtocolCode::MaxFrameSizeExceeded,
+            limits.max_frame_size,
+        )),
+        // The frame is still incomplete after the maximum size, it can only be larger.
+        None if value.len() > limits.max_frame_size => Err(Error::protocol(
+            ProtocolCode::MaxFrameSizeExceeded,
+            limits.max_frame_size,
+        )),
+        length => Ok(length),
+    }
+}
+
+/// Get the length of a frame nested in `depth` aggregates.
+pub(crate) fn get_nested_frame_length(
+    value: &[u8],
+    limits: &ParserLimits,
+    depth: usize,
+) -> crate::Result<Option<usize>> {
+    let id = match value.first() {
+        Some(id) => RespDataType::identify(*id)?,
+        None => return Ok(None),
+    };
+    let header = match find_crlf(value, 1) {
+        Some(end) => end + 2,
+        None => return Ok(None),
+    };
+    if is_streamed(value) {
+        return get_streamed_frame_length(value, id, limits, depth);
+    }
+    match id {
+        RespDataType::BulkStrings | RespDataType::BulkErrors | RespDataType::VerbatimStrings => {
+            let (_, length, _) = get_resp_multi_values(value)?;
+            if length < 0 {
+                return Ok(Some(header));
+            }
+            if length as usize > limits.max_bulk_length {
+                return Err(Error::protocol(ProtocolCode::MaxBulkLengthExceeded, 1));
+            }
+            let end = header + length as usize + 2;
+            match value.get(end - 2..end) {
+                Some(crlf) if is_cr(&crlf[0]) && is_lf(&crlf[1]) => Ok(Some(end)),
+                Some(_) => Err(Error::protocol(
+                    ProtocolCode::InvalidCrlfTerminator,
+                    end - 2,
+                )),
+                None => Ok(None),
+            }
+        }
+        RespDataType::Arrays
+        | RespDataType::Maps
+        | RespDataType::Sets
+        | RespDataType::Pushes
+        | RespDataType::Attributes => {
+            let (_, length, _) = get_resp_multi_values(value)?;
+            limits.check_aggregate(length.max(0) as usize, depth)?;
+            // Attributes are followed by the value they describe.
+            let count = match id {
+                RespDataType::Maps => length * 2,
+                RespDataType::Attributes => length.max(0) * 2 + 1,
+                _ => length,
+            };
+            let mut end = header;
+            for _ in 0..count.max(0) {
+                match get_nested_frame_length(&value[end..], limits, depth + 1)
+                    .map_err(|err| err.shift(end))?
+                {
+                    Some(n) => end += n,
+                    None => return Ok(None),
+                }
+            }
+            Ok(Some(end))
+        }
+        _ => Ok(Some(header)),
+    }
+}
+
+/// Get the length of a RESP3 streamed string or aggregate nested in `depth` aggregates.
+fn get_streamed_frame_length(
+    value: &[u8],
+    id: RespDataType,
+    limits: &ParserLimits,
+    depth: usize,
+) -> crate::Result<Option<usize>> {
+    match id {
+        RespDataType::BulkStrings => {
+            Ok(get_streamed_chunks(value, limits.max_bulk_length)?.map(|(_, end)| end))
+        }
+        RespDataType::Arrays | RespDataType::Maps | RespDataType::Sets => {
+            limits.check_aggregate(0, depth)?;
+            let count = if id.is_maps() { 2 } else { 1 };
+            let mut length = 0;
+            let mut end = 4;
+            loop {
+                match is_streamed_end(&value[end..]).map_err(|err| err.shift(end))? {
+                    Some(true) => return Ok(Some(end + 3)),
+                    Some(false) => {}
+                    None => return Ok(None),
+                }
+                length += 1;
+                limits.check_aggregate(length, depth)?;
+                for _ in 0..count {
+                    match get_nested_frame_length(&value[end..], limits, depth + 1)
+                        .map_err(|err| err.shift(end))?
+                    {
+                        Some(n) => end += n,
+                        None => return Ok(None),
+                    }
+                }
+            }
+        }
+        _ => Err(Error::protocol(ProtocolCode::StreamedTypeInvalid, 0)),
+    }
+}
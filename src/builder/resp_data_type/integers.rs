@@ -1,8 +1,8 @@
+use crate::{Error, ProtocolCode};
 use crate::{
     builder::resp_data_type::{helpers::get_resp_value, RespDataTypeTrait},
     types::RespDataTypeValue,
 };
-use anyhow::anyhow;
 
 #[derive(Debug)]
 pub struct Integers<'a> {
@@ -16,14 +16,16 @@ impl<'a> RespDataTypeTrait<'a> for Integers<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
         let (new_value, id) = get_resp_value(self.value, true)?;
         if !id.is_integers() {
-            return Err(anyhow!("NOT_INTEGERS_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotIntegersType, 0));
         }
         self.length = new_value.len() + 3;
         let value = String::from_utf8_lossy(new_value);
-        let parsed = value.parse::<i64>()?;
+        let parsed = value
+            .parse::<i64>()
+            .map_err(|_| Error::protocol(ProtocolCode::IntegersInvalidValue, 1))?;
         Ok(RespDataTypeValue::Integer(parsed))
     }
 }
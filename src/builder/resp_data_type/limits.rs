@@ -0,0 +1,94 @@
+use crate::{Error, ProtocolCode};
+
+/// Bounds enforced by the parsers, so a corrupt or hostile reply can't exhaust memory or the stack.
+/// The lengths are checked against the headers, before any nested value is parsed or any payload
+/// is copied, and each limit fails with its own error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserLimits {
+    /// The maximum number of aggregates nested in one another, a top-level array is at depth 1.
+    /// Exceeding it fails with `MAX_DEPTH_EXCEEDED`.
+    pub max_depth: usize,
+    /// The maximum payload length of a bulk string, bulk error or verbatim string, in bytes.
+    /// Exceeding it fails with `MAX_BULK_LENGTH_EXCEEDED`.
+    pub max_bulk_length: usize,
+    /// The maximum number of elements of an array, set or push, or of entries of a map or attributes.
+    /// Exceeding it fails with `MAX_AGGREGATE_LENGTH_EXCEEDED`.
+    pub max_aggregate_length: usize,
+    /// The maximum size of a whole frame, nested values included, in bytes.
+    /// Exceeding it fails with `MAX_FRAME_SIZE_EXCEEDED`, even before the frame is complete.
+    pub max_frame_size: usize,
+}
+
+impl ParserLimits {
+    /// Checks an aggregate against `max_depth` and `max_aggregate_length`, the only place both
+    /// limits are enforced so the parsers and the decoder count elements the same way.
+    /// Parameters:
+    /// - length - The number of elements, or of entries for maps and attributes. For a streamed
+    ///   aggregate, the number of elements received so far, including the one starting.
+    /// - depth - The number of aggregates the aggregate is nested in.
+    ///   Returns:
+    /// - Err - The offset is relative to the start of the aggregate, 0 for the depth and 1 for the
+    ///   length.
+    pub(crate) fn check_aggregate(&self, length: usize, depth: usize) -> crate::Result<()> {
+        if depth >= self.max_depth {
+            return Err(Error::protocol(ProtocolCode::MaxDepthExceeded, 0));
+        }
+        if length > self.max_aggregate_length {
+            return Err(Error::protocol(ProtocolCode::MaxAggregateLengthExceeded, 1));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ParserLimits {
+    /// The bulk length matches the server's default `proto-max-bulk-len` (512 MiB).
+    fn default() -> Self {
+        Self {
+            max_depth: 128,
+            max_bulk_length: 512 * 1024 * 1024,
+            max_aggregate_length: 16 * 1024 * 1024,
+            max_frame_size: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_limits {
+    use super::*;
+    use crate::builder::resp_data_type::{RespDecoder, RespParser, RespRefParser};
+    use bytes::BytesMut;
+    use tokio_util::codec::Decoder;
+
+    #[test]
+    fn test_same_across_parsers() {
+        let limits = ParserLimits {
+            max_depth: 2,
+            max_bulk_length: 5,
+            max_aggregate_length: 2,
+            max_frame_size: 64,
+        };
+        let inputs: Vec<&[u8]> = vec![
+            b"%2\r\n+a\r\n:1\r\n+b\r\n:2\r\n",
+            b"%3\r\n+a\r\n:1\r\n+b\r\n:2\r\n+c\r\n:3\r\n",
+            b"%?\r\n+a\r\n:1\r\n+b\r\n:2\r\n.\r\n",
+            b"%?\r\n+a\r\n:1\r\n+b\r\n:2\r\n+c\r\n:3\r\n.\r\n",
+            b"*?\r\n:1\r\n:2\r\n:3\r\n.\r\n",
+            b"*1\r\n~?\r\n:1\r\n.\r\n",
+            b"*1\r\n*?\r\n*0\r\n.\r\n",
+            b"|2\r\n+a\r\n:1\r\n+b\r\n:2\r\n+OK\r\n",
+        ];
+        for input in inputs {
+            let expected = RespParser::with_limits(input, limits)
+                .try_parse()
+                .map(|value| value.is_some());
+            let result = RespRefParser::with_limits(input, limits)
+                .try_parse()
+                .map(|value| value.is_some());
+            assert_eq!(format!("{expected:?}"), format!("{result:?}"), "{:?}", input);
+            let result = RespDecoder::with_limits(limits)
+                .decode(&mut BytesMut::from(input))
+                .map(|value| value.is_some());
+            assert_eq!(format!("{expected:?}"), format!("{result:?}"), "{:?}", input);
+        }
+    }
+}
@@ -1,127 +1,116 @@
-use crate::builder::resp_data_type::helpers::get_resp_multi_values;
-use crate::builder::resp_data_type::helpers::is_cr;
-use crate::builder::resp_data_type::helpers::is_lf;
+use crate::{Error, ProtocolCode};
+use crate::builder::resp_data_type::helpers::{
+    get_resp_multi_values, get_resp_value, get_streamed_values, is_streamed,
+};
 use crate::builder::resp_data_type::RespDataTypeTrait;
 use crate::builder::resp_data_type::RespParser;
-use crate::types::resp_data_kind::RespDataType;
-use crate::types::resp_data_type_iter::RespDataTypeIterator;
 use crate::types::RespDataTypeValue;
-use anyhow::anyhow;
+use std::collections::BTreeMap;
 
+/// Builds a RESP3 map into a `RespDataTypeValue::Object`, so the keys can be looked up.
+/// A map without entries (`%0\r\n`) is an empty object, RESP3 sends a missing map as a null (`_\r\n`).
 #[derive(Debug)]
 pub struct Maps<'a> {
     value: &'a [u8],
     length: usize,
 }
 impl<'a> Maps<'a> {
-    fn set_data(
-        &mut self,
-        parser: &mut RespParser,
-        data: &mut Vec<RespDataTypeValue>,
-        length: isize,
-    ) -> anyhow::Result<bool> {
-        let result = parser.parse()?;
-        data.push(result);
-        self.value = &self.value[parser.len()..];
-        Ok(length == data.len() as isize)
+    fn next_value(&self, end: &mut usize) -> crate::Result<RespDataTypeValue> {
+        let mut parser = RespParser::nested(self.value.get(*end..).unwrap_or_default());
+        let result = parser.parse().map_err(|err| err.shift(*end))?;
+        *end += parser.len();
+        Ok(result)
     }
 }
 impl<'a> RespDataTypeTrait<'a> for Maps<'a> {
     fn new(value: &'a [u8]) -> Self {
-        Self {
-            value,
-            length: value.len(),
-        }
+        Self { value, length: 0 }
     }
     fn len(&self) -> usize {
         self.length
     }
 
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
+        if is_streamed(self.value) {
+            // %?\r\n followed by the keys and values, until .\r\n
+            let (_, main_id) = get_resp_value(self.value, false)?;
+            if !main_id.is_maps() {
+                return Err(Error::protocol(ProtocolCode::NotMapsType, 0));
+            }
+            let (data, end) = get_streamed_values(self.value)?;
+            if data.len() % 2 != 0 {
+                return Err(Error::protocol(ProtocolCode::MapsValueNotFound, end - 3));
+            }
+            let mut data = data.into_iter();
+            let mut entries: BTreeMap<RespDataTypeValue, RespDataTypeValue> = BTreeMap::new();
+            while let (Some(key), Some(value)) = (data.next(), data.next()) {
+                entries.insert(key, value);
+            }
+            self.length = end;
+            return Ok(RespDataTypeValue::Object(entries));
+        }
         let (start, length, main_id) = get_resp_multi_values(self.value)?;
         if !main_id.is_maps() {
-            return Err(anyhow!("NOT_maps_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotMapsType, 0));
         }
-        let mut data: Vec<RespDataTypeValue> = Vec::new();
-        if length > 0 {
-            self.value = &self.value[start..];
-            loop {
-                let id = if let Some(v) = self.value.first()
-                    && let Ok(kind) = RespDataType::identify(*v)
-                {
-                    kind
-                } else {
-                    return Err(anyhow!("INVALID_RESP_TYPE_ID"));
-                };
-                let mut iter = RespDataTypeIterator::new(self.value);
-                if id.is_maps() {
-                    let result = self.build()?;
-                    data.push(result);
-                } else if id.is_bulk_strings() || id.is_verbatim_strings() || id.is_bulk_errors() {
-                    let _ = self.set_data(&mut RespParser::new(self.value), &mut data, length)?;
-                } else {
-                    let mut tmp_holder: Vec<u8> = Vec::new();
-                    while let Some(v) = iter.next() {
-                        tmp_holder.push(*v);
-                        if is_cr(v)
-                            && let Some(peek_values) = iter.npeek(2)
-                            && let Some(lf) = peek_values.first()
-                            && is_lf(lf)
-                            && let Some(next_id) = peek_values.get(1)
-                            && RespDataType::identify(*next_id).is_ok()
-                            && let Some(mut next_values) = iter.nnext(1)
-                        {
-                            tmp_holder.append(&mut next_values);
-                            let is_break = self.set_data(
-                                &mut RespParser::new(&tmp_holder),
-                                &mut data,
-                                length,
-                            )?;
-                            tmp_holder = vec![];
-                            if is_break {
-                                break;
-                            }
-                        }
-                    }
-
-                    if !tmp_holder.is_empty() {
-                        let _ =
-                            self.set_data(&mut RespParser::new(&tmp_holder), &mut data, length)?;
-                    }
-                }
-                if data.len() == length as usize {
-                    break;
-                }
-            }
+        let mut data: BTreeMap<RespDataTypeValue, RespDataTypeValue> = BTreeMap::new();
+        let mut end = start;
+        for _ in 0..length.max(0) {
+            let key = self.next_value(&mut end)?;
+            let value = self.next_value(&mut end)?;
+            data.insert(key, value);
         }
-        Ok(RespDataTypeValue::Array(data))
+        self.length = end;
+        Ok(RespDataTypeValue::Object(data))
     }
 }
 
 #[cfg(test)]
 pub mod test_maps {
-    use std::collections::HashMap;
-
     use super::*;
 
     #[test]
     fn test_maps() {
-        let identifier = RespDataType::Maps.to_decimal().unwrap();
-
         struct TestCase {
             pub input: Vec<u8>,
             pub expected: RespDataTypeValue,
         }
 
         let test_cases = vec![
-            // 1) Empty map: %0\r\n
+            // 1) %2\r\n+first\r\n:1\r\n+second\r\n*1\r\n#t\r\n
             TestCase {
-                // %0\r\n
                 input: vec![
-                    37, // '%'
-                    48, 13, 10, // "0\r\n" → length = 0
+                    37, 50, 13, 10, // %2\r\n
+                    43, 102, 105, 114, 115, 116, 13, 10, // +first\r\n
+                    58, 49, 13, 10, // :1\r\n
+                    43, 115, 101, 99, 111, 110, 100, 13, 10, // +second\r\n
+                    42, 49, 13, 10, 35, 116, 13, 10, // *1\r\n#t\r\n
                 ],
-                expected: RespDataTypeValue::Null,
+                expected: RespDataTypeValue::Object(BTreeMap::from([
+                    (
+                        RespDataTypeValue::String("first".into()),
+                        RespDataTypeValue::Integer(1),
+                    ),
+                    (
+                        RespDataTypeValue::String("second".into()),
+                        RespDataTypeValue::Array(vec![RespDataTypeValue::Boolean(true)]),
+                    ),
+                ])),
+            },
+            // 2) %1\r\n$3\r\nkey\r\n%1\r\n:1\r\n_\r\n
+            TestCase {
+                input: vec![
+                    37, 49, 13, 10, // %1\r\n
+                    36, 51, 13, 10, 107, 101, 121, 13, 10, // $3\r\nkey\r\n
+                    37, 49, 13, 10, 58, 49, 13, 10, 95, 13, 10, // %1\r\n:1\r\n_\r\n
+                ],
+                expected: RespDataTypeValue::Object(BTreeMap::from([(
+                    RespDataTypeValue::String("key".into()),
+                    RespDataTypeValue::Object(BTreeMap::from([(
+                        RespDataTypeValue::Integer(1),
+                        RespDataTypeValue::Null,
+                    )])),
+                )])),
             },
         ];
 
@@ -130,6 +119,22 @@ pub mod test_maps {
             let result = maps.build();
             assert!(result.is_ok(), "{:#?}", result.err());
             assert_eq!(test_case.expected, result.unwrap());
+            assert_eq!(test_case.input.len(), maps.len());
         }
     }
+
+    #[test]
+    fn test_empty_map() {
+        // %0\r\n is a map without entries, not a null: the maps are built into objects, and the
+        // previous expectation of `Null` never matched the empty array the parser returned.
+        let input = vec![
+            37, // '%'
+            48, 13, 10, // "0\r\n" → length = 0
+        ];
+        let mut maps = Maps::new(&input);
+        let result = maps.build();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(RespDataTypeValue::Object(BTreeMap::new()), result.unwrap());
+        assert_eq!(input.len(), maps.len());
+    }
 }
@@ -1,27 +1,41 @@
 pub mod arrays;
+pub mod attributes;
 pub mod big_numbers;
 pub mod booleans;
 pub mod bulk_errors;
 pub mod bulk_strings;
+pub mod decoder;
 pub mod doubles;
+pub mod encoder;
 pub mod helpers;
 pub mod integers;
+pub mod limits;
 pub mod maps;
 pub mod nulls;
 pub mod parser;
+pub mod pushes;
+pub mod ref_parser;
+pub mod sets;
 pub mod simple_errors;
 pub mod simple_strings;
 pub mod verbatim_strings;
 pub use arrays::Arrays;
+pub use attributes::Attributes;
 pub use big_numbers::BigNumbers;
 pub use booleans::Booleans;
 pub use bulk_errors::BulkErrors;
 pub use bulk_strings::BulkStrings;
+pub use decoder::RespDecoder;
 pub use doubles::Doubles;
+pub use encoder::RespEncoder;
 pub use integers::Integers;
+pub use limits::ParserLimits;
 pub use maps::Maps;
 pub use nulls::Nulls;
 pub use parser::RespParser;
+pub use pushes::Pushes;
+pub use ref_parser::RespRefParser;
+pub use sets::Sets;
 pub use simple_errors::SimpleErrors;
 pub use simple_strings::SimpleStrings;
 pub use verbatim_strings::VerbatimStrings;
@@ -34,5 +48,5 @@ pub trait RespDataTypeTrait<'a> {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue>;
+    fn build(&mut self) -> crate::Result<RespDataTypeValue>;
 }
@@ -1,8 +1,8 @@
+use crate::{Error, ProtocolCode};
 use crate::{
     builder::resp_data_type::{RespDataTypeTrait, helpers::get_resp_value},
     types::RespDataTypeValue,
 };
-use anyhow::anyhow;
 
 #[derive(Debug)]
 pub struct Nulls<'a> {
@@ -17,12 +17,12 @@ impl<'a> RespDataTypeTrait<'a> for Nulls<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
-        let (_, id) = get_resp_value(self.value, true)?;
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
+        let (new_value, id) = get_resp_value(self.value, true)?;
         if !id.is_nulls() {
-            return Err(anyhow!("NOT_NULLS_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotNullsType, 0));
         }
-        self.length = 3;
+        self.length = new_value.len() + 3;
         Ok(RespDataTypeValue::Null)
     }
 }
@@ -1,26 +1,48 @@
 use crate::builder::resp_data_type::arrays::Arrays;
+use crate::builder::resp_data_type::attributes::Attributes;
+use crate::builder::resp_data_type::helpers::get_frame_length;
 use crate::builder::resp_data_type::{
-    BigNumbers, Booleans, BulkErrors, BulkStrings, Doubles, Integers, Nulls, RespDataTypeTrait,
-    VerbatimStrings,
+    BigNumbers, Booleans, BulkErrors, BulkStrings, Doubles, Integers, Maps, Nulls, ParserLimits,
+    Pushes, RespDataTypeTrait, Sets, VerbatimStrings,
 };
 use crate::types::RespDataTypeValue;
+use crate::{Error, ProtocolCode};
 use crate::{
     builder::resp_data_type::{SimpleErrors, SimpleStrings},
     types::resp_data_kind::RespDataType,
 };
-use anyhow::anyhow;
 #[derive(Debug)]
 pub struct RespParser<'a> {
     length: usize,
     value: &'a [u8],
+    limits: Option<ParserLimits>,
 }
 
 impl<'a> RespParser<'a> {
+    /// Creates a parser enforcing the default limits.
     pub fn new(value: &'a [u8]) -> Self {
-        Self { value, length: 0 }
+        Self::with_limits(value, ParserLimits::default())
     }
 
-    fn builder<T: RespDataTypeTrait<'a>>(&mut self, b: T) -> anyhow::Result<RespDataTypeValue> {
+    /// Creates a parser enforcing the given limits.
+    pub fn with_limits(value: &'a [u8], limits: ParserLimits) -> Self {
+        Self {
+            value,
+            length: 0,
+            limits: Some(limits),
+        }
+    }
+
+    /// Creates a parser for a value nested in a frame that was already checked against the limits.
+    pub(crate) fn nested(value: &'a [u8]) -> Self {
+        Self {
+            value,
+            length: 0,
+            limits: None,
+        }
+    }
+
+    fn builder<T: RespDataTypeTrait<'a>>(&mut self, b: T) -> crate::Result<RespDataTypeValue> {
         let mut b = b;
         let value = b.build()?;
         self.length = b.len();
@@ -32,12 +54,28 @@ impl<'a> RespParser<'a> {
     pub fn len(&self) -> usize {
         self.length
     }
-    pub fn parse(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    /// Parses the first frame of the input if all of its bytes are available.
+    /// Returns `None` when the frame is incomplete and more bytes need to be read.
+    pub fn try_parse(&mut self) -> crate::Result<Option<RespDataTypeValue>> {
+        match get_frame_length(self.value, &self.limits.unwrap_or_default())? {
+            Some(_) => Ok(Some(self.build()?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn parse(&mut self) -> crate::Result<RespDataTypeValue> {
+        if let Some(limits) = &self.limits {
+            get_frame_length(self.value, limits)?;
+        }
+        self.build()
+    }
+
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
         let value = self.value;
         let identifier = match value.first() {
             Some(b) => RespDataType::identify(b.to_owned())?,
             None => {
-                return Err(anyhow!("DATA_TYPE_NOT_FOUND".to_string()));
+                return Err(Error::protocol(ProtocolCode::DataTypeNotFound, 0));
             }
         };
         let value = match identifier {
@@ -52,7 +90,10 @@ impl<'a> RespParser<'a> {
             RespDataType::Arrays => self.builder(Arrays::new(value))?,
             RespDataType::BulkErrors => self.builder(BulkErrors::new(value))?,
             RespDataType::VerbatimStrings => self.builder(VerbatimStrings::new(value))?,
-            _ => return Err(anyhow!("not supported")),
+            RespDataType::Maps => self.builder(Maps::new(value))?,
+            RespDataType::Sets => self.builder(Sets::new(value))?,
+            RespDataType::Pushes => self.builder(Pushes::new(value))?,
+            RespDataType::Attributes => self.builder(Attributes::new(value))?,
         };
         Ok(value)
     }
@@ -61,6 +102,7 @@ impl<'a> RespParser<'a> {
 #[cfg(test)]
 pub mod test_result {
     use super::*;
+    use std::collections::{BTreeMap, BTreeSet};
     #[test]
     fn test_get() {
         let input: Vec<u8> = vec![43, 79, 75, 13, 10]; //+Ok\r\n
@@ -70,4 +112,303 @@ pub mod test_result {
         let value = result.unwrap();
         assert_eq!(value, RespDataTypeValue::String("OK".to_string()));
     }
+
+    #[test]
+    fn test_try_parse_complete() {
+        struct TestCase {
+            pub input: Vec<u8>,
+            pub expected: RespDataTypeValue,
+            pub length: usize,
+        }
+        let test_cases = vec![
+            TestCase {
+                // $5\r\nhello\r\n
+                input: b"$5\r\nhello\r\n".to_vec(),
+                expected: RespDataTypeValue::String("hello".to_string()),
+                length: 11,
+            },
+            TestCase {
+                // :1\r\n:2\r\n, only the first frame is parsed
+                input: b":1\r\n:2\r\n".to_vec(),
+                expected: RespDataTypeValue::Integer(1),
+                length: 4,
+            },
+            TestCase {
+                // *2\r\n$1\r\na\r\n$-1\r\n+OK\r\n
+                input: b"*2\r\n$1\r\na\r\n$-1\r\n+OK\r\n".to_vec(),
+                expected: RespDataTypeValue::Array(vec![
+                    RespDataTypeValue::String("a".to_string()),
+                    RespDataTypeValue::Null,
+                ]),
+                length: 16,
+            },
+        ];
+        for test_case in test_cases {
+            let mut parser = RespParser::new(&test_case.input);
+            let result = parser.try_parse();
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(Some(test_case.expected), result.unwrap());
+            assert_eq!(test_case.length, parser.len());
+        }
+    }
+
+    #[test]
+    fn test_try_parse_incomplete() {
+        let inputs: Vec<&[u8]> = vec![
+            b"",
+            b"+OK",
+            b"+OK\r",
+            b"$5\r\nhel",
+            b"$5\r\nhello",
+            b"$5\r\nhello\r",
+            b"*2\r\n:1\r\n",
+            b"*2\r\n:1\r\n$3\r\nab",
+            b"%1\r\n+key\r\n",
+        ];
+        for input in inputs {
+            let mut parser = RespParser::new(input);
+            let result = parser.try_parse();
+            assert!(result.is_ok(), "{:?}: {:#?}", input, result.err());
+            assert_eq!(None, result.unwrap(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_try_parse_invalid() {
+        // $2\r\nhello\r\n, the payload is longer than the declared length
+        let mut parser = RespParser::new(b"$2\r\nhello\r\n");
+        assert!(parser.try_parse().is_err());
+    }
+
+    #[test]
+    fn test_streamed() {
+        struct TestCase {
+            pub input: Vec<u8>,
+            pub expected: RespDataTypeValue,
+        }
+        let test_cases = vec![
+            TestCase {
+                // $?\r\n;4\r\nHell\r\n;5\r\no wor\r\n;1\r\nd\r\n;0\r\n
+                input: b"$?\r\n;4\r\nHell\r\n;5\r\no wor\r\n;1\r\nd\r\n;0\r\n".to_vec(),
+                expected: RespDataTypeValue::String("Hello word".to_string()),
+            },
+            TestCase {
+                // A chunk may contain CRLF, only the length counts.
+                input: b"$?\r\n;4\r\na\r\nb\r\n;0\r\n".to_vec(),
+                expected: RespDataTypeValue::String("a\r\nb".to_string()),
+            },
+            TestCase {
+                input: b"*?\r\n:1\r\n:2\r\n:3\r\n.\r\n".to_vec(),
+                expected: RespDataTypeValue::Array(vec![
+                    RespDataTypeValue::Integer(1),
+                    RespDataTypeValue::Integer(2),
+                    RespDataTypeValue::Integer(3),
+                ]),
+            },
+            TestCase {
+                input: b"*?\r\n.\r\n".to_vec(),
+                expected: RespDataTypeValue::Array(vec![]),
+            },
+            TestCase {
+                input: b"%?\r\n+a\r\n:1\r\n+b\r\n*?\r\n$?\r\n;2\r\nhi\r\n;0\r\n.\r\n.\r\n".to_vec(),
+                expected: RespDataTypeValue::Object(BTreeMap::from([
+                    (
+                        RespDataTypeValue::String("a".to_string()),
+                        RespDataTypeValue::Integer(1),
+                    ),
+                    (
+                        RespDataTypeValue::String("b".to_string()),
+                        RespDataTypeValue::Array(vec![RespDataTypeValue::String("hi".to_string())]),
+                    ),
+                ])),
+            },
+            TestCase {
+                input: b"~?\r\n+a\r\n+b\r\n+a\r\n.\r\n".to_vec(),
+                expected: RespDataTypeValue::Set(BTreeSet::from([
+                    RespDataTypeValue::String("a".to_string()),
+                    RespDataTypeValue::String("b".to_string()),
+                ])),
+            },
+        ];
+        for test_case in test_cases {
+            let mut parser = RespParser::new(&test_case.input);
+            let result = parser.try_parse();
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(Some(test_case.expected), result.unwrap());
+            assert_eq!(test_case.input.len(), parser.len());
+        }
+    }
+
+    #[test]
+    fn test_streamed_incomplete() {
+        let inputs: Vec<&[u8]> = vec![
+            b"$?\r\n",
+            b"$?\r\n;4\r\nHell\r\n",
+            b"$?\r\n;4\r\nHe",
+            b"*?\r\n:1\r\n",
+            b"*?\r\n:1\r\n.\r",
+            b"%?\r\n+a\r\n",
+        ];
+        for input in inputs {
+            let mut parser = RespParser::new(input);
+            let result = parser.try_parse();
+            assert!(result.is_ok(), "{:?}: {:#?}", input, result.err());
+            assert_eq!(None, result.unwrap(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_streamed_invalid() {
+        let inputs: Vec<&[u8]> = vec![
+            b"$?\r\n+4\r\nHell\r\n;0\r\n",
+            b"$?\r\n;x\r\nHell\r\n;0\r\n",
+            b"*?\r\n:1\r\n.x\r\n",
+            b">?\r\n:1\r\n.\r\n",
+            b"%?\r\n+a\r\n.\r\n",
+        ];
+        for input in inputs {
+            let mut parser = RespParser::new(input);
+            assert!(parser.try_parse().is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        struct TestCase {
+            pub input: Vec<u8>,
+            pub limits: ParserLimits,
+            pub expected: ProtocolCode,
+        }
+        let limits = ParserLimits {
+            max_depth: 2,
+            max_bulk_length: 5,
+            max_aggregate_length: 3,
+            max_frame_size: 64,
+        };
+        let test_cases = vec![
+            TestCase {
+                // The declared length is checked before the payload is received.
+                input: b"$6\r\nhel".to_vec(),
+                limits,
+                expected: ProtocolCode::MaxBulkLengthExceeded,
+            },
+            TestCase {
+                input: b"=10\r\ntxt:hello!\r\n".to_vec(),
+                limits,
+                expected: ProtocolCode::MaxBulkLengthExceeded,
+            },
+            TestCase {
+                input: b"*999999999\r\n".to_vec(),
+                limits,
+                expected: ProtocolCode::MaxAggregateLengthExceeded,
+            },
+            TestCase {
+                input: b"$?\r\n;4\r\nHell\r\n;2\r\no!\r\n;0\r\n".to_vec(),
+                limits,
+                expected: ProtocolCode::MaxBulkLengthExceeded,
+            },
+            TestCase {
+                // The running total is checked before the chunk payload is received.
+                input: b"$?\r\n;4\r\nHell\r\n;2\r\n".to_vec(),
+                limits,
+                expected: ProtocolCode::MaxBulkLengthExceeded,
+            },
+            TestCase {
+                input: b"$?\r\n;18446744073709551615\r\n".to_vec(),
+                limits: ParserLimits::default(),
+                expected: ProtocolCode::MaxBulkLengthExceeded,
+            },
+            TestCase {
+                // The chunk offsets would overflow.
+                input: b"$?\r\n;18446744073709551615\r\n".to_vec(),
+                limits: ParserLimits {
+                    max_bulk_length: usize::MAX,
+                    max_frame_size: usize::MAX,
+                    ..limits
+                },
+                expected: ProtocolCode::InvalidLength,
+            },
+            TestCase {
+                input: b"*?\r\n:1\r\n:2\r\n:3\r\n:4\r\n".to_vec(),
+                limits,
+                expected: ProtocolCode::MaxAggregateLengthExceeded,
+            },
+            TestCase {
+                input: b"%4\r\n".to_vec(),
+                limits,
+                expected: ProtocolCode::MaxAggregateLengthExceeded,
+            },
+            TestCase {
+                input: b"*1\r\n*1\r\n*1\r\n:1\r\n".to_vec(),
+                limits,
+                expected: ProtocolCode::MaxDepthExceeded,
+            },
+            TestCase {
+                input: b"*1\r\n|1\r\n+a\r\n:1\r\n*0\r\n".to_vec(),
+                limits,
+                expected: ProtocolCode::MaxDepthExceeded,
+            },
+            TestCase {
+                input: b"*3\r\n+hello\r\n+hello\r\n+hello\r\n".to_vec(),
+                limits: ParserLimits {
+                    max_frame_size: 16,
+                    ..limits
+                },
+                expected: ProtocolCode::MaxFrameSizeExceeded,
+            },
+            TestCase {
+                // The frame is incomplete but already larger than the maximum size.
+                input: [b"+".to_vec(), vec![b'a'; 64]].concat(),
+                limits,
+                expected: ProtocolCode::MaxFrameSizeExceeded,
+            },
+        ];
+        for test_case in test_cases {
+            let mut parser = RespParser::with_limits(&test_case.input, test_case.limits);
+            let result = parser.try_parse();
+            assert!(
+                matches!(&result, Err(Error::Protocol { code, .. }) if *code == test_case.expected),
+                "{:?}: {:#?}",
+                test_case.input,
+                result
+            );
+            let mut parser = RespParser::with_limits(&test_case.input, test_case.limits);
+            assert!(parser.parse().is_err());
+        }
+    }
+
+    #[test]
+    fn test_limits_respected() {
+        let limits = ParserLimits {
+            max_depth: 2,
+            max_bulk_length: 5,
+            max_aggregate_length: 2,
+            max_frame_size: 32,
+        };
+        // *2\r\n*2\r\n$5\r\nhello\r\n:1\r\n_\r\n
+        let input = b"*2\r\n*2\r\n$5\r\nhello\r\n:1\r\n_\r\n";
+        let mut parser = RespParser::with_limits(input, limits);
+        let result = parser.try_parse();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(input.len(), parser.len());
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        // Deeply nested input fails with an error instead of overflowing the stack.
+        let input = b"*1\r\n".repeat(100_000);
+        let mut parser = RespParser::new(&input);
+        let result = parser.parse();
+        assert!(
+            matches!(
+                &result,
+                Err(Error::Protocol {
+                    code: ProtocolCode::MaxDepthExceeded,
+                    ..
+                })
+            ),
+            "{:#?}",
+            result
+        );
+    }
 }
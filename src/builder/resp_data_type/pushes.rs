@@ -0,0 +1,87 @@
+use crate::builder::resp_data_type::RespDataTypeTrait;
+use crate::builder::resp_data_type::RespParser;
+use crate::builder::resp_data_type::helpers::get_resp_multi_values;
+use crate::types::RespDataTypeValue;
+use crate::{Error, ProtocolCode};
+
+#[derive(Debug)]
+pub struct Pushes<'a> {
+    value: &'a [u8],
+    length: usize,
+}
+
+impl<'a> RespDataTypeTrait<'a> for Pushes<'a> {
+    fn new(value: &'a [u8]) -> Self {
+        Self { value, length: 0 }
+    }
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Builds an out-of-band message sent by the server, e.g. a pub/sub message or an invalidation.
+    /// The first element is the kind of the message.
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
+        let (start, length, main_id) = get_resp_multi_values(self.value)?;
+        if !main_id.is_pushes() {
+            return Err(Error::protocol(ProtocolCode::NotPushesType, 0));
+        }
+        let mut data: Vec<RespDataTypeValue> = Vec::new();
+        let mut end = start;
+        for _ in 0..length.max(0) {
+            let mut parser = RespParser::nested(self.value.get(end..).unwrap_or_default());
+            data.push(parser.parse().map_err(|err| err.shift(end))?);
+            end += parser.len();
+        }
+        self.length = end;
+        Ok(RespDataTypeValue::Push(data))
+    }
+}
+
+#[cfg(test)]
+pub mod test_pushes {
+    use super::*;
+
+    #[test]
+    fn test_pushes() {
+        struct TestCase {
+            pub input: Vec<u8>,
+            pub expected: RespDataTypeValue,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                // >3\r\n$7\r\nmessage\r\n$7\r\nchannel\r\n$5\r\nhello\r\n
+                input: b">3\r\n$7\r\nmessage\r\n$7\r\nchannel\r\n$5\r\nhello\r\n".to_vec(),
+                expected: RespDataTypeValue::Push(vec![
+                    RespDataTypeValue::String("message".into()),
+                    RespDataTypeValue::String("channel".into()),
+                    RespDataTypeValue::String("hello".into()),
+                ]),
+            },
+            TestCase {
+                // >2\r\n$10\r\ninvalidate\r\n*1\r\n$5\r\nmykey\r\n
+                input: b">2\r\n$10\r\ninvalidate\r\n*1\r\n$5\r\nmykey\r\n".to_vec(),
+                expected: RespDataTypeValue::Push(vec![
+                    RespDataTypeValue::String("invalidate".into()),
+                    RespDataTypeValue::Array(vec![RespDataTypeValue::String("mykey".into())]),
+                ]),
+            },
+        ];
+
+        for test_case in test_cases {
+            let mut pushes = Pushes::new(&test_case.input);
+            let result = pushes.build();
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(test_case.expected, result.unwrap());
+            assert_eq!(test_case.input.len(), pushes.len());
+        }
+    }
+
+    #[test]
+    fn test_incomplete() {
+        let mut parser = RespParser::new(b">2\r\n+message\r\n");
+        let result = parser.try_parse();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(None, result.unwrap());
+    }
+}
@@ -0,0 +1,470 @@
+use crate::builder::resp_data_type::ParserLimits;
+use crate::builder::resp_data_type::helpers::{
+    find_crlf, get_streamed_chunks, is_cr, is_lf, is_streamed_end,
+};
+use crate::types::resp_data_kind::RespDataType;
+use crate::types::resp_value_ref::RespValueRef;
+use crate::{Error, ProtocolCode};
+
+/// A single-pass parser that borrows the payloads from the input instead of copying them.
+/// Every byte is visited once, and incomplete frames are detected during the same pass.
+#[derive(Debug)]
+pub struct RespRefParser<'a> {
+    length: usize,
+    value: &'a [u8],
+    limits: ParserLimits,
+}
+
+impl<'a> RespRefParser<'a> {
+    /// Creates a parser enforcing the default limits.
+    pub fn new(value: &'a [u8]) -> Self {
+        Self::with_limits(value, ParserLimits::default())
+    }
+
+    /// Creates a parser enforcing the given limits.
+    pub fn with_limits(value: &'a [u8], limits: ParserLimits) -> Self {
+        Self {
+            value,
+            length: 0,
+            limits,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of bytes consumed by the last parsed frame.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Parses the first frame of the input if all of its bytes are available.
+    /// Returns `None` when the frame is incomplete and more bytes need to be read.
+    pub fn try_parse(&mut self) -> crate::Result<Option<RespValueRef<'a>>> {
+        match self.parse_at(0, 0)? {
+            Some((_, end)) if end > self.limits.max_frame_size => Err(Error::protocol(
+                ProtocolCode::MaxFrameSizeExceeded,
+                self.limits.max_frame_size,
+            )),
+            Some((value, end)) => {
+                self.length = end;
+                Ok(Some(value))
+            }
+            // The frame is still incomplete after the maximum size, it can only be larger.
+            None if self.value.len() > self.limits.max_frame_size => Err(Error::protocol(
+                ProtocolCode::MaxFrameSizeExceeded,
+                self.limits.max_frame_size,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    pub fn parse(&mut self) -> crate::Result<RespValueRef<'a>> {
+        match self.try_parse()? {
+            Some(value) => Ok(value),
+            None if self.value.is_empty() => {
+                Err(Error::protocol(ProtocolCode::DataTypeNotFound, 0))
+            }
+            None => Err(Error::protocol(ProtocolCode::IncompleteFrame, 0)),
+        }
+    }
+
+    /// Parses the frame starting at `start`, nested in `depth` aggregates.
+    /// Returns:
+    /// - Some((value, end)) - The value and the index right after the frame.
+    /// - None - The frame is incomplete.
+    fn parse_at(
+        &self,
+        start: usize,
+        depth: usize,
+    ) -> crate::Result<Option<(RespValueRef<'a>, usize)>> {
+        let id = match self.value.get(start) {
+            Some(id) => RespDataType::identify(*id).map_err(|err| err.shift(start))?,
+            None => return Ok(None),
+        };
+        let (line, header) = match find_crlf(self.value, start + 1) {
+            Some(end) => (&self.value[start + 1..end], end + 2),
+            None => return Ok(None),
+        };
+        if line == b"?" {
+            return self.parse_streamed_at(id, start, header, depth);
+        }
+        let value = match id {
+            RespDataType::SimpleStrings => RespValueRef::SimpleString(line),
+            RespDataType::SimpleErrors => RespValueRef::SimpleError(line),
+            RespDataType::Integers => match Self::to_str(line, start + 1)?.parse::<i64>() {
+                Ok(value) => RespValueRef::Integer(value),
+                Err(_) => {
+                    return Err(Error::protocol(
+                        ProtocolCode::IntegersInvalidValue,
+                        start + 1,
+                    ));
+                }
+            },
+            RespDataType::Doubles => match Self::to_str(line, start + 1)?.parse::<f64>() {
+                Ok(value) => RespValueRef::Double(value),
+                Err(_) => {
+                    return Err(Error::protocol(
+                        ProtocolCode::DoublesInvalidValue,
+                        start + 1,
+                    ));
+                }
+            },
+            RespDataType::Nulls => RespValueRef::Null,
+            RespDataType::Booleans => match line {
+                b"t" => RespValueRef::Boolean(true),
+                b"f" => RespValueRef::Boolean(false),
+                _ => {
+                    return Err(Error::protocol(
+                        ProtocolCode::BooleanInvalidValue,
+                        start + 1,
+                    ));
+                }
+            },
+            RespDataType::BigNumbers => {
+                let digits = line.strip_prefix(b"-").unwrap_or(line);
+                if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+                    return Err(Error::protocol(
+                        ProtocolCode::BigNumbersInvalidValue,
+                        start + 1,
+                    ));
+                }
+                RespValueRef::BigNumber(line)
+            }
+            RespDataType::BulkStrings
+            | RespDataType::BulkErrors
+            | RespDataType::VerbatimStrings => {
+                let length = Self::to_length(line, start + 1)?;
+                if length < 0 {
+                    return Ok(Some((RespValueRef::Null, header)));
+                }
+                if length as usize > self.limits.max_bulk_length {
+                    return Err(Error::protocol(
+                        ProtocolCode::MaxBulkLengthExceeded,
+                        start + 1,
+                    ));
+                }
+                let end = header + length as usize;
+                let data = match self.value.get(header..end + 2) {
+                    Some(data) if is_cr(&data[data.len() - 2]) && is_lf(&data[data.len() - 1]) => {
+                        &data[..data.len() - 2]
+                    }
+                    Some(_) => {
+                        return Err(Error::protocol(ProtocolCode::InvalidCrlfTerminator, end));
+                    }
+                    None => return Ok(None),
+                };
+                let value = match id {
+                    _ if data.is_empty() => RespValueRef::BulkString(data),
+                    RespDataType::BulkErrors => RespValueRef::BulkError(data),
+                    RespDataType::VerbatimStrings => match (data.get(..3), data.get(4..)) {
+                        (Some(encoding), payload) => {
+                            RespValueRef::VerbatimString(encoding, payload.unwrap_or_default())
+                        }
+                        _ => {
+                            return Err(Error::protocol(
+                                ProtocolCode::VerbatimStringsEncodingError,
+                                header,
+                            ));
+                        }
+                    },
+                    _ => RespValueRef::BulkString(data),
+                };
+                return Ok(Some((value, end + 2)));
+            }
+            RespDataType::Arrays | RespDataType::Sets | RespDataType::Pushes => {
+                let length = Self::to_length(line, start + 1)?;
+                // Only arrays have a null form, `RespParser` reads a negative set or push as empty.
+                if length < 0 && id.is_arrays() {
+                    return Ok(Some((RespValueRef::Null, header)));
+                }
+                let length = length.max(0);
+                self.limits
+                    .check_aggregate(length as usize, depth)
+                    .map_err(|err| err.shift(start))?;
+                let mut end = header;
+                let mut values = Vec::with_capacity(self.capacity(length, header, 3));
+                for _ in 0..length {
+                    match self.parse_at(end, depth + 1)? {
+                        Some((value, next)) => {
+                            values.push(value);
+                            end = next;
+                        }
+                        None => return Ok(None),
+                    }
+                }
+                let value = match id {
+                    RespDataType::Sets => RespValueRef::Set(values),
+                    RespDataType::Pushes => RespValueRef::Push(values),
+                    _ => RespValueRef::Array(values),
+                };
+                return Ok(Some((value, end)));
+            }
+            RespDataType::Maps | RespDataType::Attributes => {
+                let length = Self::to_length(line, start + 1)?.max(0);
+                self.limits
+                    .check_aggregate(length as usize, depth)
+                    .map_err(|err| err.shift(start))?;
+                let mut end = header;
+                let mut values = Vec::with_capacity(self.capacity(length, header, 6));
+                for _ in 0..length {
+                    let Some((key, next)) = self.parse_at(end, depth + 1)? else {
+                        return Ok(None);
+                    };
+                    let Some((value, next)) = self.parse_at(next, depth + 1)? else {
+                        return Ok(None);
+                    };
+                    values.push((key, value));
+                    end = next;
+                }
+                if !id.is_attributes() {
+                    return Ok(Some((RespValueRef::Map(values), end)));
+                }
+                // Attributes are followed by the value they describe.
+                let Some((value, end)) = self.parse_at(end, depth + 1)? else {
+                    return Ok(None);
+                };
+                return Ok(Some((
+                    RespValueRef::Attributed(values, Box::new(value)),
+                    end,
+                )));
+            }
+        };
+        Ok(Some((value, header)))
+    }
+
+    /// Parses the RESP3 streamed string or aggregate starting at `start`, its header ends at `header`.
+    fn parse_streamed_at(
+        &self,
+        id: RespDataType,
+        start: usize,
+        header: usize,
+        depth: usize,
+    ) -> crate::Result<Option<(RespValueRef<'a>, usize)>> {
+        if id.is_bulk_strings() {
+            let Some((chunks, end)) =
+                get_streamed_chunks(&self.value[start..], self.limits.max_bulk_length)
+                    .map_err(|err| err.shift(start))?
+            else {
+                return Ok(None);
+            };
+            let chunks = chunks
+                .into_iter()
+                .map(|chunk| &self.value[start + chunk.start..start + chunk.end])
+                .collect();
+            return Ok(Some((RespValueRef::StreamedString(chunks), start + end)));
+        }
+        if !(id.is_arrays() || id.is_maps() || id.is_sets()) {
+            return Err(Error::protocol(ProtocolCode::StreamedTypeInvalid, start));
+        }
+        self.limits
+            .check_aggregate(0, depth)
+            .map_err(|err| err.shift(start))?;
+        // Keys and values are pushed separately, a map entry is counted when its key starts.
+        let entry = if id.is_maps() { 2 } else { 1 };
+        let mut end = header;
+        let mut values = Vec::new();
+        loop {
+            match is_streamed_end(&self.value[end..]).map_err(|err| err.shift(end))? {
+                Some(true) => break,
+                Some(false) => {}
+                None => return Ok(None),
+            }
+            if values.len() % entry == 0 {
+                self.limits
+                    .check_aggregate(values.len() / entry + 1, depth)
+                    .map_err(|err| err.shift(start))?;
+            }
+            let Some((value, next)) = self.parse_at(end, depth + 1)? else {
+                return Ok(None);
+            };
+            values.push(value);
+            end = next;
+        }
+        let value = match id {
+            RespDataType::Maps => {
+                if values.len() % 2 != 0 {
+                    return Err(Error::protocol(ProtocolCode::MapsValueNotFound, end));
+                }
+                let mut values = values.into_iter();
+                let mut entries = Vec::new();
+                while let (Some(key), Some(value)) = (values.next(), values.next()) {
+                    entries.push((key, value));
+                }
+                RespValueRef::Map(entries)
+            }
+            RespDataType::Sets => RespValueRef::Set(values),
+            _ => RespValueRef::Array(values),
+        };
+        Ok(Some((value, end + 3)))
+    }
+
+    /// The number of elements to reserve for an aggregate whose elements start at `from`.
+    /// The declared length comes from the input, so it is capped by the number of elements the
+    /// remaining bytes can hold, each being at least `size` bytes (e.g. `_\r\n`).
+    fn capacity(&self, length: isize, from: usize, size: usize) -> usize {
+        (length.max(0) as usize).min(self.value.len().saturating_sub(from) / size)
+    }
+
+    fn to_str(value: &[u8], offset: usize) -> crate::Result<&str> {
+        std::str::from_utf8(value).map_err(|_| Error::protocol(ProtocolCode::InvalidUtf8, offset))
+    }
+
+    fn to_length(value: &[u8], offset: usize) -> crate::Result<isize> {
+        match Self::to_str(value, offset)?.parse::<isize>() {
+            Ok(length) => Ok(length),
+            Err(_) => Err(Error::protocol(ProtocolCode::InvalidLength, offset)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_ref_parser {
+    use super::*;
+    use crate::builder::resp_data_type::RespParser;
+
+    #[test]
+    fn test_same_as_owned_parser() {
+        let inputs: Vec<&[u8]> = vec![
+            b"+OK\r\n",
+            b"-ERR unknown command\r\n",
+            b":-42\r\n",
+            b"$5\r\nhello\r\n",
+            b"$0\r\n\r\n",
+            b"$-1\r\n",
+            b"$4\r\n\x00\xFF\r\n\r\n",
+            b"_\r\n",
+            b"#t\r\n",
+            b",3.25\r\n",
+            b",inf\r\n",
+            b",-inf\r\n",
+            b",nan\r\n",
+            b"(-3492890328409238509324850943850943825024385\r\n",
+            b"!21\r\nSYNTAX invalid syntax\r\n",
+            b"=9\r\ntxt:hello\r\n",
+            b"*-1\r\n",
+            b"~-1\r\n",
+            b">-1\r\n",
+            b"*3\r\n:1\r\n$1\r\na\r\n*1\r\n#f\r\n",
+            b"%2\r\n+first\r\n:1\r\n+second\r\n~2\r\n:1\r\n:1\r\n",
+            b">2\r\n$7\r\nmessage\r\n$5\r\nhello\r\n",
+            b"|1\r\n+ttl\r\n:3600\r\n+OK\r\n",
+            b"$?\r\n;4\r\nHell\r\n;5\r\no wor\r\n;1\r\nd\r\n;0\r\n",
+            b"$?\r\n;0\r\n",
+            b"*?\r\n:1\r\n$?\r\n;1\r\na\r\n;0\r\n*?\r\n.\r\n.\r\n",
+            b"%?\r\n+a\r\n:1\r\n+b\r\n~?\r\n#t\r\n.\r\n.\r\n",
+        ];
+        for input in inputs {
+            let mut owned_parser = RespParser::new(input);
+            let expected = owned_parser.parse();
+            assert!(expected.is_ok(), "{:?}: {:#?}", input, expected.err());
+            let mut parser = RespRefParser::new(input);
+            let result = parser.parse();
+            assert!(result.is_ok(), "{:?}: {:#?}", input, result.err());
+            assert_eq!(expected.unwrap(), result.unwrap().to_owned(), "{:?}", input);
+            assert_eq!(owned_parser.len(), parser.len(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let input = b"*2\r\n$5\r\nhello\r\n+world\r\n".to_vec();
+        let mut parser = RespRefParser::new(&input);
+        let result = parser.parse();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let RespValueRef::Array(values) = result.unwrap() else {
+            panic!("NOT_ARRAY");
+        };
+        assert_eq!(Some("hello"), values[0].as_str());
+        assert_eq!(Some("world"), values[1].as_str());
+        // The payload points into the input, it is not a copy.
+        let payload = values[0].as_bytes().unwrap();
+        assert_eq!(input[8..13].as_ptr(), payload.as_ptr());
+    }
+
+    #[test]
+    fn test_incomplete() {
+        let inputs: Vec<&[u8]> = vec![
+            b"",
+            b"+OK",
+            b"$5\r\nhel",
+            b"$5\r\nhello\r",
+            b"*2\r\n:1\r\n",
+            b"%1\r\n+key\r\n",
+            b"|1\r\n+ttl\r\n:3600\r\n",
+            b"$?\r\n;4\r\nHell\r\n",
+            b"*?\r\n:1\r\n.",
+        ];
+        for input in inputs {
+            let mut parser = RespRefParser::new(input);
+            let result = parser.try_parse();
+            assert!(result.is_ok(), "{:?}: {:#?}", input, result.err());
+            assert_eq!(None, result.unwrap(), "{:?}", input);
+            assert!(parser.parse().is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        let inputs: Vec<&[u8]> = vec![
+            b"$2\r\nhello\r\n",
+            b"#x\r\n",
+            b"(12a\r\n",
+            b":abc\r\n",
+            b"?\r\n",
+        ];
+        for input in inputs {
+            let mut parser = RespRefParser::new(input);
+            assert!(parser.try_parse().is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = ParserLimits {
+            max_depth: 2,
+            max_bulk_length: 5,
+            max_aggregate_length: 3,
+            max_frame_size: 64,
+        };
+        let test_cases: Vec<(&[u8], ProtocolCode)> = vec![
+            (b"$6\r\nhel", ProtocolCode::MaxBulkLengthExceeded),
+            (
+                b"$?\r\n;4\r\nHell\r\n;2\r\n",
+                ProtocolCode::MaxBulkLengthExceeded,
+            ),
+            (
+                b"$?\r\n;18446744073709551615\r\n",
+                ProtocolCode::MaxBulkLengthExceeded,
+            ),
+            (b"*999999999\r\n", ProtocolCode::MaxAggregateLengthExceeded),
+            (b"*1\r\n*1\r\n*1\r\n:1\r\n", ProtocolCode::MaxDepthExceeded),
+            (&[b'+'; 65], ProtocolCode::MaxFrameSizeExceeded),
+        ];
+        for (input, expected) in test_cases {
+            let mut parser = RespRefParser::with_limits(input, limits);
+            let result = parser.try_parse();
+            assert!(
+                matches!(&result, Err(Error::Protocol { code, .. }) if *code == expected),
+                "{:?}: {:#?}",
+                input,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_header() {
+        // The declared lengths are within the limits, but only a few bytes follow them.
+        let inputs: Vec<&[u8]> = vec![b"*16777216\r\n:1\r\n", b"%16777216\r\n:1\r\n"];
+        for input in inputs {
+            let parser = RespRefParser::new(input);
+            assert_eq!(1, parser.capacity(16777216, input.len() - 4, 3));
+            let mut parser = RespRefParser::new(input);
+            let result = parser.try_parse();
+            assert!(result.is_ok(), "{:?}: {:#?}", input, result.err());
+            assert_eq!(None, result.unwrap(), "{:?}", input);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::builder::resp_data_type::RespDataTypeTrait;
+use crate::builder::resp_data_type::RespParser;
+use crate::builder::resp_data_type::helpers::{
+    get_resp_multi_values, get_resp_value, get_streamed_values, is_streamed,
+};
+use crate::types::RespDataTypeValue;
+use crate::{Error, ProtocolCode};
+use std::collections::BTreeSet;
+
+#[derive(Debug)]
+pub struct Sets<'a> {
+    value: &'a [u8],
+    length: usize,
+}
+
+impl<'a> RespDataTypeTrait<'a> for Sets<'a> {
+    fn new(value: &'a [u8]) -> Self {
+        Self { value, length: 0 }
+    }
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Builds the set, duplicated members are kept once.
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
+        if is_streamed(self.value) {
+            // ~?\r\n followed by the members, until .\r\n
+            let (_, main_id) = get_resp_value(self.value, false)?;
+            if !main_id.is_sets() {
+                return Err(Error::protocol(ProtocolCode::NotSetsType, 0));
+            }
+            let (data, end) = get_streamed_values(self.value)?;
+            self.length = end;
+            return Ok(RespDataTypeValue::Set(data.into_iter().collect()));
+        }
+        let (start, length, main_id) = get_resp_multi_values(self.value)?;
+        if !main_id.is_sets() {
+            return Err(Error::protocol(ProtocolCode::NotSetsType, 0));
+        }
+        let mut data: BTreeSet<RespDataTypeValue> = BTreeSet::new();
+        let mut end = start;
+        for _ in 0..length.max(0) {
+            let mut parser = RespParser::nested(self.value.get(end..).unwrap_or_default());
+            data.insert(parser.parse().map_err(|err| err.shift(end))?);
+            end += parser.len();
+        }
+        self.length = end;
+        Ok(RespDataTypeValue::Set(data))
+    }
+}
+
+#[cfg(test)]
+pub mod test_sets {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_sets() {
+        struct TestCase {
+            pub input: Vec<u8>,
+            pub expected: RespDataTypeValue,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                // ~0\r\n
+                input: b"~0\r\n".to_vec(),
+                expected: RespDataTypeValue::Set(BTreeSet::new()),
+            },
+            TestCase {
+                // ~3\r\n+b\r\n+a\r\n:1\r\n
+                input: b"~3\r\n+b\r\n+a\r\n:1\r\n".to_vec(),
+                expected: RespDataTypeValue::Set(BTreeSet::from([
+                    RespDataTypeValue::String("a".into()),
+                    RespDataTypeValue::String("b".into()),
+                    RespDataTypeValue::Integer(1),
+                ])),
+            },
+            TestCase {
+                // ~3\r\n$1\r\na\r\n+a\r\n_\r\n, duplicated members are kept once
+                input: b"~3\r\n$1\r\na\r\n+a\r\n_\r\n".to_vec(),
+                expected: RespDataTypeValue::Set(BTreeSet::from([
+                    RespDataTypeValue::String("a".into()),
+                    RespDataTypeValue::Null,
+                ])),
+            },
+            TestCase {
+                // ~2\r\n*1\r\n:1\r\n%1\r\n+k\r\n~1\r\n#t\r\n
+                input: b"~2\r\n*1\r\n:1\r\n%1\r\n+k\r\n~1\r\n#t\r\n".to_vec(),
+                expected: RespDataTypeValue::Set(BTreeSet::from([
+                    RespDataTypeValue::Array(vec![RespDataTypeValue::Integer(1)]),
+                    RespDataTypeValue::Object(BTreeMap::from([(
+                        RespDataTypeValue::String("k".into()),
+                        RespDataTypeValue::Set(BTreeSet::from([RespDataTypeValue::Boolean(true)])),
+                    )])),
+                ])),
+            },
+        ];
+
+        for test_case in test_cases {
+            let mut sets = Sets::new(&test_case.input);
+            let result = sets.build();
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(test_case.expected, result.unwrap());
+            assert_eq!(test_case.input.len(), sets.len());
+        }
+    }
+
+    #[test]
+    fn test_nested_in_arrays() {
+        // *2\r\n~1\r\n:1\r\n+OK\r\n
+        let input = b"*2\r\n~1\r\n:1\r\n+OK\r\n".to_vec();
+        let mut parser = RespParser::new(&input);
+        let result = parser.parse();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::Array(vec![
+                RespDataTypeValue::Set(BTreeSet::from([RespDataTypeValue::Integer(1)])),
+                RespDataTypeValue::String("OK".into()),
+            ]),
+            result.unwrap()
+        );
+        assert_eq!(input.len(), parser.len());
+    }
+
+    #[test]
+    fn test_incomplete() {
+        let mut parser = RespParser::new(b"~2\r\n:1\r\n");
+        let result = parser.try_parse();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(None, result.unwrap());
+    }
+
+    #[test]
+    fn test_not_sets() {
+        let mut sets = Sets::new(b"*0\r\n");
+        assert!(sets.build().is_err());
+    }
+}
@@ -2,7 +2,7 @@ use crate::builder::resp_data_type::RespDataTypeTrait;
 use crate::builder::resp_data_type::helpers::get_resp_value;
 use crate::types::RespDataTypeValue;
 use crate::types::RespErrorKind;
-use anyhow::anyhow;
+use crate::{Error, ProtocolCode};
 
 #[derive(Debug)]
 pub struct SimpleErrors<'a> {
@@ -17,10 +17,10 @@ impl<'a> RespDataTypeTrait<'a> for SimpleErrors<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
         let (new_value, id) = get_resp_value(self.value, true)?;
         if !id.is_simple_errors() {
-            return Err(anyhow!("NOT_SIMPLE_ERRORS_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotSimpleErrorsType, 0));
         }
         self.length = new_value.len() + 3;
         let data = String::from_utf8_lossy(new_value).to_string();
@@ -31,6 +31,7 @@ impl<'a> RespDataTypeTrait<'a> for SimpleErrors<'a> {
 
 #[cfg(test)]
 pub mod test_simple_errors {
+    use crate::types::RespErrorDetails;
     use crate::types::resp_data_kind::RespDataType;
 
     use super::*;
@@ -52,4 +53,25 @@ pub mod test_simple_errors {
             result.unwrap()
         );
     }
+
+    #[test]
+    fn test_details() {
+        let input = b"-MOVED 3999 127.0.0.1:6381\r\n";
+        let mut serrors = SimpleErrors::new(input);
+        let result = serrors.build();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = result.unwrap();
+        assert_eq!(
+            RespDataTypeValue::Error(RespErrorKind::Moved, "3999 127.0.0.1:6381".to_string()),
+            result
+        );
+        assert_eq!(
+            Some(RespErrorDetails::Moved {
+                slot: 3999,
+                address: "127.0.0.1:6381".to_string(),
+            }),
+            result.error_details()
+        );
+        assert_eq!(input.len(), serrors.len());
+    }
 }
@@ -1,7 +1,7 @@
 use crate::builder::resp_data_type::RespDataTypeTrait;
 use crate::builder::resp_data_type::helpers::get_resp_value;
 use crate::types::RespDataTypeValue;
-use anyhow::anyhow;
+use crate::{Error, ProtocolCode};
 #[derive(Debug)]
 pub struct SimpleStrings<'a> {
     pub length: usize,
@@ -15,10 +15,10 @@ impl<'a> RespDataTypeTrait<'a> for SimpleStrings<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
         let (new_value, id) = get_resp_value(self.value, true)?;
         if !id.is_simple_strings() {
-            return Err(anyhow!("NOT_SIMPLE_STRINGS_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotSimpleStringsType, 0));
         }
         self.length = new_value.len() + 3;
         let data = String::from_utf8_lossy(new_value).to_string();
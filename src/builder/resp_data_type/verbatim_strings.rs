@@ -1,7 +1,7 @@
 use crate::builder::resp_data_type::RespDataTypeTrait;
 use crate::builder::resp_data_type::helpers::get_resp_multi_values;
 use crate::types::{RespDataTypeValue, VerbatimEncoding};
-use anyhow::anyhow;
+use crate::{Error, ProtocolCode};
 
 #[derive(Debug)]
 pub struct VerbatimStrings<'a> {
@@ -16,33 +16,41 @@ impl<'a> RespDataTypeTrait<'a> for VerbatimStrings<'a> {
     fn len(&self) -> usize {
         self.length
     }
-    fn build(&mut self) -> anyhow::Result<RespDataTypeValue> {
+    fn build(&mut self) -> crate::Result<RespDataTypeValue> {
         let (start, length, id) = get_resp_multi_values(self.value)?;
         if !id.is_verbatim_strings() {
-            return Err(anyhow!("NOT_VERBATIM_STRINGS_TYPE"));
+            return Err(Error::protocol(ProtocolCode::NotVerbatimStringsType, 0));
         }
         if length == 0 {
+            self.length = start + 2;
             return Ok(RespDataTypeValue::String("".to_string()));
         } else if length <= -1 {
+            self.length = start;
             return Ok(RespDataTypeValue::Null);
         }
 
         let end = start + length as usize;
-        if (length as usize) > end {
-            return Err(anyhow!("VERBATIM_STRINGS_LENGTH_ERROR"));
+        if end > self.value.len() {
+            return Err(Error::protocol(
+                ProtocolCode::VerbatimStringsLengthError,
+                start,
+            ));
         }
         let encoding = if let Some(values) = &self.value.get(start..start + 3) {
             VerbatimEncoding::from(&String::from_utf8_lossy(values))
         } else {
-            return Err(anyhow!("VERBATIM_STRINGS_ENCODING_ERROR"));
-        };
-        let message = if let Some(values) = &self.value.get(start + 4..end) {
-            String::from_utf8_lossy(values).to_string()
-        } else {
-            String::new()
+            return Err(Error::protocol(
+                ProtocolCode::VerbatimStringsEncodingError,
+                start,
+            ));
         };
+        let message = self.value.get(start + 4..end).unwrap_or_default().to_vec();
         self.length = end + 2;
-        Ok(RespDataTypeValue::VerbatimString(message, encoding))
+        // Binary payloads that are not valid UTF-8 are kept as-is, with their encoding.
+        match String::from_utf8(message) {
+            Ok(message) => Ok(RespDataTypeValue::VerbatimString(message, encoding)),
+            Err(err) => Ok(RespDataTypeValue::VerbatimBytes(err.into_bytes(), encoding)),
+        }
     }
 }
 #[cfg(test)]
@@ -93,6 +101,11 @@ pub mod test_verbatim_strings {
                     VerbatimEncoding::Unknown("jsn".to_string()),
                 ),
             },
+            TestCase {
+                // =6\r\ntxt:\xFF\x00\r\n
+                input: vec![61, 54, 13, 10, 116, 120, 116, 58, 255, 0, 13, 10],
+                expected: RespDataTypeValue::VerbatimBytes(vec![0xFF, 0x00], VerbatimEncoding::Txt),
+            },
         ];
         for test_case in test_cases {
             let mut verbatim_strings = VerbatimStrings::new(&test_case.input);
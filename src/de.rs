@@ -0,0 +1,640 @@
+use crate::Error;
+use crate::types::RespDataTypeValue;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    Unexpected, VariantAccess, Visitor,
+};
+use std::vec::IntoIter;
+
+/// Deserializes a reply into any type implementing `serde::Deserialize`.
+/// - Maps, and RESP2 flat arrays of field/value pairs (e.g. `HGETALL`), become structs or maps.
+/// - Arrays, sets and pushes become sequences or tuples.
+/// - Nulls become `None`, or `()`.
+/// - Numbers and booleans are also read from strings, since RESP2 sends hash fields as strings.
+/// - Attributes are ignored, only the value they describe is deserialized.
+///
+/// Failures are `Error::Deserialize`, with the path of the value that failed, e.g. `users[2].age`.
+/// Error replies are `Error::Server`, the same as with `FromRespValue`.
+/// Parameters:
+/// - value - The reply to deserialize.
+pub fn from_resp<T: DeserializeOwned>(value: RespDataTypeValue) -> crate::Result<T> {
+    T::deserialize(RespDeserializer::new(value))
+}
+
+/// A `serde::Deserializer` that takes ownership of a reply.
+#[derive(Debug)]
+pub struct RespDeserializer {
+    value: RespDataTypeValue,
+}
+
+impl RespDeserializer {
+    pub fn new(value: RespDataTypeValue) -> Self {
+        Self {
+            value: without_attributes(value),
+        }
+    }
+}
+
+/// Drops the attributes sent with the value.
+fn without_attributes(value: RespDataTypeValue) -> RespDataTypeValue {
+    match value {
+        RespDataTypeValue::Attributed(_, value) => without_attributes(*value),
+        value => value,
+    }
+}
+
+/// Describes a map key as a path segment, e.g. `name` or `[1]`.
+fn key_segment(key: &RespDataTypeValue) -> String {
+    match key.without_attributes() {
+        RespDataTypeValue::String(key) => key.to_owned(),
+        RespDataTypeValue::Integer(key) => format!("[{key}]"),
+        key => format!("[{key:?}]"),
+    }
+}
+
+/// Describes a value for the "invalid type" errors.
+fn unexpected(value: &RespDataTypeValue) -> Unexpected<'_> {
+    match value {
+        RespDataTypeValue::String(value) | RespDataTypeValue::VerbatimString(value, _) => {
+            Unexpected::Str(value)
+        }
+        RespDataTypeValue::Bytes(value) | RespDataTypeValue::VerbatimBytes(value, _) => {
+            Unexpected::Bytes(value)
+        }
+        RespDataTypeValue::Boolean(value) => Unexpected::Bool(*value),
+        RespDataTypeValue::Integer(value) => Unexpected::Signed(*value),
+        RespDataTypeValue::Double(value) => Unexpected::Float(value.0),
+        RespDataTypeValue::Infinity => Unexpected::Float(f64::INFINITY),
+        RespDataTypeValue::NegativeInfinity => Unexpected::Float(f64::NEG_INFINITY),
+        RespDataTypeValue::Nan => Unexpected::Float(f64::NAN),
+        RespDataTypeValue::BigNumber(_) => Unexpected::Other("big number"),
+        RespDataTypeValue::Error(_, _) => Unexpected::Other("error"),
+        RespDataTypeValue::Array(_) | RespDataTypeValue::Push(_) => Unexpected::Seq,
+        RespDataTypeValue::Set(_) => Unexpected::Other("set"),
+        RespDataTypeValue::Object(_) => Unexpected::Map,
+        RespDataTypeValue::Attributed(_, value) => unexpected(value),
+        RespDataTypeValue::Null => Unexpected::Unit,
+    }
+}
+
+/// The error returned when the visitor can't take the value, an error reply is `Error::Server`.
+fn invalid_type(value: RespDataTypeValue, expected: &dyn de::Expected) -> Error {
+    match value.into_reply() {
+        Ok(value) => de::Error::invalid_type(unexpected(&value), expected),
+        Err(err) => err,
+    }
+}
+
+/// Reads a number from a string value, otherwise the value is deserialized as is.
+macro_rules! deserialize_number {
+    ($method:ident, $visit:ident, $ty:ty) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+            match self.value {
+                RespDataTypeValue::String(value) => match value.parse::<$ty>() {
+                    Ok(number) => visitor.$visit(number),
+                    Err(_) => Err(de::Error::invalid_value(Unexpected::Str(&value), &visitor)),
+                },
+                _ => self.deserialize_any(visitor),
+            }
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for RespDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        match self.value.into_reply()? {
+            RespDataTypeValue::String(value) | RespDataTypeValue::VerbatimString(value, _) => {
+                visitor.visit_string(value)
+            }
+            RespDataTypeValue::Bytes(value) | RespDataTypeValue::VerbatimBytes(value, _) => {
+                visitor.visit_byte_buf(value)
+            }
+            RespDataTypeValue::Boolean(value) => visitor.visit_bool(value),
+            RespDataTypeValue::Integer(value) => visitor.visit_i64(value),
+            RespDataTypeValue::Double(value) => visitor.visit_f64(value.0),
+            RespDataTypeValue::Infinity => visitor.visit_f64(f64::INFINITY),
+            RespDataTypeValue::NegativeInfinity => visitor.visit_f64(f64::NEG_INFINITY),
+            RespDataTypeValue::Nan => visitor.visit_f64(f64::NAN),
+            RespDataTypeValue::BigNumber(value) => {
+                if let Ok(number) = i64::try_from(&value) {
+                    visitor.visit_i64(number)
+                } else if let Ok(number) = u64::try_from(&value) {
+                    visitor.visit_u64(number)
+                } else if let Ok(number) = i128::try_from(&value) {
+                    visitor.visit_i128(number)
+                } else {
+                    visitor.visit_string(value.to_string())
+                }
+            }
+            RespDataTypeValue::Array(values) | RespDataTypeValue::Push(values) => {
+                visitor.visit_seq(SeqDeserializer::new(values))
+            }
+            RespDataTypeValue::Set(values) => {
+                visitor.visit_seq(SeqDeserializer::new(values.into_iter().collect()))
+            }
+            RespDataTypeValue::Object(values) => {
+                visitor.visit_map(MapDeserializer::new(values.into_iter().collect()))
+            }
+            RespDataTypeValue::Null => visitor.visit_unit(),
+            // Removed by `into_reply`.
+            value @ (RespDataTypeValue::Error(_, _) | RespDataTypeValue::Attributed(_, _)) => {
+                Err(invalid_type(value, &visitor))
+            }
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        // RESP2 sends booleans as integers, and hash fields as strings.
+        match self.value {
+            RespDataTypeValue::Integer(0) => visitor.visit_bool(false),
+            RespDataTypeValue::Integer(1) => visitor.visit_bool(true),
+            RespDataTypeValue::String(value) => match RespDataTypeValue::parse_bool(&value) {
+                Some(value) => visitor.visit_bool(value),
+                None => Err(de::Error::invalid_value(Unexpected::Str(&value), &visitor)),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    deserialize_number!(deserialize_i8, visit_i8, i8);
+    deserialize_number!(deserialize_i16, visit_i16, i16);
+    deserialize_number!(deserialize_i32, visit_i32, i32);
+    deserialize_number!(deserialize_i64, visit_i64, i64);
+    deserialize_number!(deserialize_i128, visit_i128, i128);
+    deserialize_number!(deserialize_u8, visit_u8, u8);
+    deserialize_number!(deserialize_u16, visit_u16, u16);
+    deserialize_number!(deserialize_u32, visit_u32, u32);
+    deserialize_number!(deserialize_u64, visit_u64, u64);
+    deserialize_number!(deserialize_u128, visit_u128, u128);
+    deserialize_number!(deserialize_f32, visit_f32, f32);
+    deserialize_number!(deserialize_f64, visit_f64, f64);
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        match self.value {
+            RespDataTypeValue::Integer(value) => visitor.visit_string(value.to_string()),
+            RespDataTypeValue::Double(value) => visitor.visit_string(value.to_string()),
+            RespDataTypeValue::BigNumber(value) => visitor.visit_string(value.to_string()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        match self.value {
+            RespDataTypeValue::String(value) | RespDataTypeValue::VerbatimString(value, _) => {
+                visitor.visit_byte_buf(value.into_bytes())
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        match self.value {
+            RespDataTypeValue::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        match self.value {
+            RespDataTypeValue::Null => visitor.visit_unit(),
+            value => Err(invalid_type(value, &visitor)),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        match self.value {
+            RespDataTypeValue::Array(_)
+            | RespDataTypeValue::Push(_)
+            | RespDataTypeValue::Set(_) => self.deserialize_any(visitor),
+            // A `Vec<u8>` asks for a sequence, binary payloads are sent as strings.
+            RespDataTypeValue::String(value) => visitor.visit_seq(de::value::SeqDeserializer::new(
+                value.into_bytes().into_iter(),
+            )),
+            RespDataTypeValue::Bytes(value) | RespDataTypeValue::VerbatimBytes(value, _) => {
+                visitor.visit_seq(de::value::SeqDeserializer::new(value.into_iter()))
+            }
+            value => Err(invalid_type(value, &visitor)),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        match self.value {
+            RespDataTypeValue::Object(_) => self.deserialize_any(visitor),
+            // RESP2 sends maps as flat arrays of field/value pairs.
+            RespDataTypeValue::Array(values) if values.len() % 2 == 0 => {
+                let mut values = values.into_iter();
+                let mut entries = Vec::with_capacity(values.len() / 2);
+                while let (Some(key), Some(value)) = (values.next(), values.next()) {
+                    entries.push((key, value));
+                }
+                visitor.visit_map(MapDeserializer::new(entries))
+            }
+            value => Err(invalid_type(value, &visitor)),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    /// Unit variants are read from strings, other variants from single entry maps.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        match self.value {
+            RespDataTypeValue::String(value) => visitor.visit_enum(value.into_deserializer()),
+            RespDataTypeValue::Object(values) if values.len() == 1 => {
+                let Some((variant, value)) = values.into_iter().next() else {
+                    return Err(de::Error::custom("DESERIALIZE_VARIANT_NOT_FOUND"));
+                };
+                let segment = key_segment(&variant);
+                visitor
+                    .visit_enum(EnumDeserializer { variant, value })
+                    .map_err(|err| err.at(&segment))
+            }
+            value => Err(invalid_type(value, &visitor)),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+/// Deserializes the elements of an array, set or push, and adds the index to the error path.
+struct SeqDeserializer {
+    values: IntoIter<RespDataTypeValue>,
+    index: usize,
+}
+
+impl SeqDeserializer {
+    fn new(values: Vec<RespDataTypeValue>) -> Self {
+        Self {
+            values: values.into_iter(),
+            index: 0,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> crate::Result<Option<T::Value>> {
+        let Some(value) = self.values.next() else {
+            return Ok(None);
+        };
+        let index = self.index;
+        self.index += 1;
+        seed.deserialize(RespDeserializer::new(value))
+            .map(Some)
+            .map_err(|err| err.at(&format!("[{index}]")))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// Deserializes the entries of a map, and adds the key to the error path.
+struct MapDeserializer {
+    entries: IntoIter<(RespDataTypeValue, RespDataTypeValue)>,
+    value: Option<(String, RespDataTypeValue)>,
+}
+
+impl MapDeserializer {
+    fn new(entries: Vec<(RespDataTypeValue, RespDataTypeValue)>) -> Self {
+        Self {
+            entries: entries.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> crate::Result<Option<K::Value>> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        let segment = key_segment(&key);
+        let key = seed
+            .deserialize(RespDeserializer::new(key))
+            .map_err(|err| err.at(&segment))?;
+        self.value = Some((segment, value));
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> crate::Result<V::Value> {
+        let Some((segment, value)) = self.value.take() else {
+            return Err(de::Error::custom("DESERIALIZE_VALUE_NOT_FOUND"));
+        };
+        seed.deserialize(RespDeserializer::new(value))
+            .map_err(|err| err.at(&segment))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// Deserializes an enum variant sent as a single entry map, `{variant: value}`.
+struct EnumDeserializer {
+    variant: RespDataTypeValue,
+    value: RespDataTypeValue,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = RespDeserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> crate::Result<(V::Value, Self::Variant)> {
+        let variant = seed.deserialize(RespDeserializer::new(self.variant))?;
+        Ok((variant, RespDeserializer::new(self.value)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for RespDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> crate::Result<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> crate::Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> crate::Result<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+#[cfg(test)]
+pub mod test_de {
+    use super::*;
+    use crate::builder::resp_data_type::RespParser;
+    use crate::types::{FromRespValue, RespErrorKind};
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    fn parse(input: &[u8]) -> RespDataTypeValue {
+        let mut parser = RespParser::new(input);
+        let result = parser.parse();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        result.unwrap()
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct User {
+        name: String,
+        age: u32,
+        email: Option<String>,
+        admin: bool,
+    }
+
+    #[test]
+    fn test_structs() {
+        struct TestCase {
+            pub input: Vec<u8>,
+            pub expected: User,
+        }
+        let test_cases = vec![
+            TestCase {
+                // RESP3 HGETALL, the fields are still strings
+                input: b"%3\r\n$4\r\nname\r\n$3\r\nann\r\n$3\r\nage\r\n$2\r\n42\r\n$5\r\nadmin\r\n$1\r\n1\r\n"
+                    .to_vec(),
+                expected: User {
+                    name: "ann".to_string(),
+                    age: 42,
+                    email: None,
+                    admin: true,
+                },
+            },
+            TestCase {
+                // RESP2 HGETALL, a flat array of field/value pairs
+                input: b"*8\r\n$4\r\nname\r\n$3\r\nbob\r\n$3\r\nage\r\n:7\r\n$5\r\nemail\r\n$7\r\nb@x.com\r\n$5\r\nadmin\r\n#f\r\n"
+                    .to_vec(),
+                expected: User {
+                    name: "bob".to_string(),
+                    age: 7,
+                    email: Some("b@x.com".to_string()),
+                    admin: false,
+                },
+            },
+        ];
+        for test_case in test_cases {
+            let result = from_resp::<User>(parse(&test_case.input));
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(test_case.expected, result.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_values() {
+        let result = from_resp::<Vec<Option<i64>>>(parse(b"*3\r\n:1\r\n_\r\n$1\r\n3\r\n"));
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(vec![Some(1), None, Some(3)], result.unwrap());
+
+        let result = from_resp::<(String, f64, bool)>(parse(b"*3\r\n+a\r\n,1.5\r\n#t\r\n"));
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(("a".to_string(), 1.5, true), result.unwrap());
+
+        let result = from_resp::<HashMap<String, i64>>(parse(b"%2\r\n+a\r\n:1\r\n+b\r\n:2\r\n"));
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]),
+            result.unwrap()
+        );
+
+        let result = from_resp::<Option<String>>(parse(b"$-1\r\n"));
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(None, result.unwrap());
+
+        let result = from_resp::<Vec<u8>>(parse(b"$2\r\n\xFF\x00\r\n"));
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(vec![0xFF, 0x00], result.unwrap());
+
+        let result = from_resp::<i64>(parse(b"|1\r\n+ttl\r\n:1\r\n:10\r\n"));
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(10, result.unwrap());
+    }
+
+    #[test]
+    fn test_server_errors() {
+        // Error replies fail as with `FromRespValue`, whatever the requested type.
+        let inputs: Vec<&[u8]> = vec![
+            b"-WRONGTYPE wrong kind\r\n",
+            b"*2\r\n:1\r\n-WRONGTYPE wrong kind\r\n",
+            b"|1\r\n+ttl\r\n:1\r\n-WRONGTYPE wrong kind\r\n",
+        ];
+        for input in inputs {
+            let result = from_resp::<Vec<i64>>(parse(input));
+            assert!(
+                matches!(&result, Err(Error::Server(RespErrorKind::WrongType, message)) if message == "wrong kind"),
+                "{:?}: {:#?}",
+                input,
+                result
+            );
+        }
+        let result = from_resp::<Option<String>>(parse(b"-ERR failed\r\n"));
+        assert!(
+            matches!(&result, Err(Error::Server(RespErrorKind::Err, _))),
+            "{:#?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_same_as_from_resp_value() {
+        let values = vec![
+            RespDataTypeValue::String("true".to_string()),
+            RespDataTypeValue::String("0".to_string()),
+            RespDataTypeValue::String("no".to_string()),
+            RespDataTypeValue::Integer(1),
+            RespDataTypeValue::Integer(2),
+            RespDataTypeValue::Boolean(false),
+        ];
+        for value in values {
+            let expected = bool::from_resp_value(value.clone()).ok();
+            assert_eq!(
+                expected,
+                from_resp::<bool>(value.clone()).ok(),
+                "{:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_enums() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Role {
+            Admin,
+            Guest(String),
+        }
+        let result = from_resp::<Vec<Role>>(parse(b"*2\r\n+Admin\r\n%1\r\n+Guest\r\n+ann\r\n"));
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            vec![Role::Admin, Role::Guest("ann".to_string())],
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_error_path() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Users {
+            users: Vec<User>,
+        }
+        struct TestCase {
+            pub input: Vec<u8>,
+            pub path: &'static str,
+        }
+        let test_cases = vec![
+            TestCase {
+                input:
+                    b"%1\r\n+users\r\n*1\r\n%3\r\n+name\r\n+ann\r\n+age\r\n+old\r\n+admin\r\n#t\r\n"
+                        .to_vec(),
+                path: "users[0].age",
+            },
+            TestCase {
+                input: b"%1\r\n+users\r\n*1\r\n%2\r\n+name\r\n+ann\r\n+age\r\n:-1\r\n".to_vec(),
+                path: "users[0].age",
+            },
+            TestCase {
+                input: b"%1\r\n+users\r\n+none\r\n".to_vec(),
+                path: "users",
+            },
+            TestCase {
+                input: b"%1\r\n+users\r\n*1\r\n%1\r\n+name\r\n+ann\r\n".to_vec(),
+                path: "users[0]",
+            },
+        ];
+        for test_case in test_cases {
+            let result = from_resp::<Users>(parse(&test_case.input));
+            assert!(
+                matches!(&result, Err(Error::Deserialize { path, .. }) if path == test_case.path),
+                "{:#?}",
+                result
+            );
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use crate::types::{RespErrorDetails, RespErrorKind};
+use strum_macros::Display;
+
+/// The errors returned by the crate.
+/// The `String` carried by most variants is a SCREAMING_SNAKE code (e.g. `GET_KEY_REQUIRED`)
+/// identifying the exact failure, while the variant tells the kind of failure. `Protocol` and
+/// `Timeout` carry typed codes instead, displayed the same way, so callers can match on them.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Reading from or writing to the connection failed.
+    #[error("IO_ERROR: {0}")]
+    Io(#[from] std::io::Error),
+    /// The bytes received are not valid RESP.
+    /// `offset` is the index, from the start of the frame, of the byte where the problem was found.
+    #[error("{code} (offset {offset})")]
+    Protocol { code: ProtocolCode, offset: usize },
+    /// A command was built with missing or invalid arguments, it was never sent.
+    #[error("{0}")]
+    InvalidCommand(String),
+    /// The server answered with an error where a successful reply was required, e.g. in the handshake.
+    #[error("SERVER_ERROR: {0:?}, {1}")]
+    Server(RespErrorKind, String),
+    /// The server answered with a reply of an unexpected shape.
+    #[error("{0}")]
+    UnexpectedReply(String),
+    /// A value can't be encoded in the requested protocol.
+    #[error("{0}")]
+    Encode(String),
+    /// The connection or pool configuration is invalid.
+    #[error("{0}")]
+    InvalidConfig(String),
+    /// An operation did not complete in time.
+    #[error("{0}")]
+    Timeout(TimeoutKind),
+    /// The connection was closed before the reply was received.
+    #[error("CONNECTION_CLOSED")]
+    ConnectionClosed,
+    /// A reply can't be deserialized into the requested type.
+    /// `path` locates the value that failed, e.g. `users[2].age`, it is empty for the reply itself.
+    #[error("{message}{}", location(.path))]
+    Deserialize { message: String, path: String },
+}
+
+/// The code of a `Protocol` error, displayed in SCREAMING_SNAKE case, e.g. `INVALID_LENGTH`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProtocolCode {
+    // The frame structure.
+    DataTypeNotFound,
+    InvalidRespDataType,
+    InvalidLength,
+    InvalidCrlfTerminator,
+    InvalidUtf8,
+    IncompleteFrame,
+    NotSupported,
+    // The value of a type.
+    IntegersInvalidValue,
+    DoublesInvalidValue,
+    BooleanInvalidValue,
+    BigNumbersInvalidValue,
+    BigNumbersParsingError,
+    BulkStringsLengthError,
+    BulkErrorsLengthError,
+    VerbatimStringsLengthError,
+    VerbatimStringsEncodingError,
+    MapsValueNotFound,
+    StreamedTypeInvalid,
+    StreamedStringChunkInvalid,
+    StreamedEndInvalid,
+    StreamedEndNotFound,
+    // A builder given a value of another type.
+    NotSimpleStringsType,
+    NotSimpleErrorsType,
+    NotIntegersType,
+    NotBulkStringsType,
+    NotBulkErrorsType,
+    NotVerbatimStringsType,
+    NotArraysType,
+    NotNullsType,
+    NotBooleansType,
+    NotDoublesType,
+    NotBigNumbersType,
+    NotMapsType,
+    NotAttributesType,
+    NotSetsType,
+    NotPushesType,
+    // The `ParserLimits`.
+    MaxDepthExceeded,
+    MaxBulkLengthExceeded,
+    MaxAggregateLengthExceeded,
+    MaxFrameSizeExceeded,
+}
+
+impl ProtocolCode {
+    /// Whether the code is a reply exceeding one of the `ParserLimits`.
+    pub fn is_limit_exceeded(&self) -> bool {
+        matches!(
+            self,
+            Self::MaxDepthExceeded
+                | Self::MaxBulkLengthExceeded
+                | Self::MaxAggregateLengthExceeded
+                | Self::MaxFrameSizeExceeded
+        )
+    }
+}
+
+/// The operation of a `Timeout` error, displayed as its code, e.g. `RESPONSE_TIMEOUT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display)]
+pub enum TimeoutKind {
+    /// Opening the connection, including the TLS and `HELLO` handshakes.
+    #[strum(to_string = "CONNECT_TIMEOUT")]
+    Connect,
+    /// Writing the commands to the socket.
+    #[strum(to_string = "WRITE_TIMEOUT")]
+    Write,
+    /// Receiving the replies of the commands.
+    #[strum(to_string = "RESPONSE_TIMEOUT")]
+    Response,
+    /// Waiting for a connection of the pool.
+    #[strum(to_string = "POOL_CHECKOUT_TIMEOUT")]
+    PoolCheckout,
+}
+
+fn location(path: &str) -> String {
+    match path {
+        "" => String::new(),
+        path => format!(" (at {path})"),
+    }
+}
+
+impl Error {
+    /// Creates a `Protocol` error.
+    /// Parameters:
+    /// - code - The error code.
+    /// - offset - The index of the byte where the problem was found.
+    pub fn protocol(code: ProtocolCode, offset: usize) -> Self {
+        Self::Protocol { code, offset }
+    }
+
+    /// Moves the offset of a `Protocol` error found in a nested value, so it is relative to the
+    /// enclosing frame. Other errors are returned unchanged.
+    /// Parameters:
+    /// - start - The index where the nested value starts in the enclosing frame.
+    pub fn shift(self, start: usize) -> Self {
+        match self {
+            Self::Protocol { code, offset } => Self::Protocol {
+                code,
+                offset: offset + start,
+            },
+            error => error,
+        }
+    }
+
+    /// Whether the error is a `Timeout`, the operation may still succeed if attempted again.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout(_))
+    }
+
+    /// Whether the error left the connection unusable: an IO failure, a closed connection, a
+    /// reply that can't be parsed or a partial write.
+    /// A reply exceeding the parser limits closes the connection too, but it is not counted since
+    /// the same reply would exceed them again on a new connection.
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            Self::Io(_) | Self::ConnectionClosed => true,
+            Self::Protocol { .. } => !self.is_limit_exceeded(),
+            Self::Timeout(kind) => *kind == TimeoutKind::Write,
+            _ => false,
+        }
+    }
+
+    /// Whether the error is a reply exceeding one of the `ParserLimits`.
+    pub fn is_limit_exceeded(&self) -> bool {
+        matches!(self, Self::Protocol { code, .. } if code.is_limit_exceeded())
+    }
+
+    /// Returns the typed payload of a `Server` error, e.g. the slot and address of `MOVED`.
+    pub fn details(&self) -> Option<RespErrorDetails> {
+        match self {
+            Self::Server(kind, message) => RespErrorDetails::parse(*kind, message),
+            _ => None,
+        }
+    }
+
+    /// Adds a segment in front of the path of a `Deserialize` error, as the error goes up through
+    /// the enclosing values. Other errors are returned unchanged.
+    /// Parameters:
+    /// - segment - A map key, e.g. `name`, or an index, e.g. `[2]`.
+    pub(crate) fn at(self, segment: &str) -> Self {
+        match self {
+            Self::Deserialize { message, path } => {
+                let path = if path.is_empty() || path.starts_with('[') {
+                    format!("{segment}{path}")
+                } else {
+                    format!("{segment}.{path}")
+                };
+                Self::Deserialize { message, path }
+            }
+            error => error,
+        }
+    }
+
+    /// Copies the error so it can be sent to every caller waiting on a failed connection.
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            Self::Io(err) => Self::Io(std::io::Error::new(err.kind(), err.to_string())),
+            Self::Protocol { code, offset } => Self::Protocol {
+                code: *code,
+                offset: *offset,
+            },
+            Self::InvalidCommand(message) => Self::InvalidCommand(message.clone()),
+            Self::Server(kind, message) => Self::Server(*kind, message.clone()),
+            Self::UnexpectedReply(message) => Self::UnexpectedReply(message.clone()),
+            Self::Encode(message) => Self::Encode(message.clone()),
+            Self::InvalidConfig(message) => Self::InvalidConfig(message.clone()),
+            Self::Timeout(kind) => Self::Timeout(*kind),
+            Self::ConnectionClosed => Self::ConnectionClosed,
+            Self::Deserialize { message, path } => Self::Deserialize {
+                message: message.clone(),
+                path: path.clone(),
+            },
+        }
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: std::fmt::Display>(message: T) -> Self {
+        Self::Deserialize {
+            message: message.to_string(),
+            path: String::new(),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+pub mod test_error {
+    use super::*;
+
+    #[test]
+    fn test_shift() {
+        let error = Error::protocol(ProtocolCode::InvalidLength, 2).shift(10);
+        assert!(
+            matches!(
+                error,
+                Error::Protocol {
+                    code: ProtocolCode::InvalidLength,
+                    offset: 12
+                }
+            ),
+            "{error:#?}"
+        );
+        let error = Error::ConnectionClosed.shift(10);
+        assert!(matches!(error, Error::ConnectionClosed));
+    }
+
+    #[test]
+    fn test_details() {
+        let error = Error::Server(RespErrorKind::Moved, "3999 127.0.0.1:6381".to_string());
+        assert_eq!(
+            Some(RespErrorDetails::Moved {
+                slot: 3999,
+                address: "127.0.0.1:6381".to_string(),
+            }),
+            error.details()
+        );
+        assert_eq!(None, Error::ConnectionClosed.details());
+    }
+
+    #[test]
+    fn test_is_connection_error() {
+        assert!(Error::ConnectionClosed.is_connection_error());
+        assert!(Error::protocol(ProtocolCode::InvalidCrlfTerminator, 4).is_connection_error());
+        assert!(Error::Timeout(TimeoutKind::Write).is_connection_error());
+        assert!(!Error::Timeout(TimeoutKind::Response).is_connection_error());
+        let error = Error::protocol(ProtocolCode::MaxBulkLengthExceeded, 1);
+        assert!(error.is_limit_exceeded());
+        assert!(!error.is_connection_error());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            "GET_KEY_REQUIRED",
+            Error::InvalidCommand("GET_KEY_REQUIRED".to_string()).to_string()
+        );
+        assert_eq!(
+            "INVALID_LENGTH (offset 3)",
+            Error::protocol(ProtocolCode::InvalidLength, 3).to_string()
+        );
+        assert_eq!(
+            "INVALID_UTF8 (offset 0)",
+            Error::protocol(ProtocolCode::InvalidUtf8, 0).to_string()
+        );
+        assert_eq!(
+            "POOL_CHECKOUT_TIMEOUT",
+            Error::Timeout(TimeoutKind::PoolCheckout).to_string()
+        );
+        let error: Error = serde::de::Error::custom("invalid type");
+        assert_eq!("invalid type", error.to_string());
+        assert_eq!(
+            "invalid type (at users[2].age)",
+            error.at("age").at("[2]").at("users").to_string()
+        );
+    }
+}
@@ -1,4 +1,8 @@
 pub mod builder;
+pub mod de;
+pub mod error;
 pub mod transport;
 pub mod types;
-pub use transport::Client;
+pub use de::from_resp;
+pub use error::{Error, ProtocolCode, Result, TimeoutKind};
+pub use transport::{Client, Pipeline, Pool, PoolConfig};
@@ -1,71 +1,295 @@
-use crate::builder::commands::AuthConfig;
+use crate::Error;
 use crate::transport::connection::{ConnectionBuilder, ConnectionConfig};
-use crate::transport::execute::Execute;
-use crate::types::RespDataTypeValue;
+use crate::transport::multiplexer::Multiplexer;
+use crate::transport::pipeline::Pipeline;
+use crate::transport::timeouts::Timeouts;
 use crate::types::command_kind::CommandKind;
-use anyhow::anyhow;
-use std::sync::Arc;
-use tokio::net::TcpStream;
-use tokio::sync::RwLock;
+use crate::types::{ExpectedReplies, ExpiryKind, FromRespValue, RespDataTypeValue, ServerInfo};
+use serde_json::Value;
+use std::sync::{Arc, RwLock};
+use tokio::sync::{Mutex, broadcast};
 
+/// A client for a single connection.
+/// The connection is multiplexed, so a client can be cloned and shared by many tasks; concurrent
+/// commands are pipelined on the same socket and every caller receives its own reply.
+/// With `ConnectionConfig::reconnect` set, a broken connection is reopened and the handshake is
+/// replayed, for every clone at once.
 #[derive(Debug, Clone)]
 pub struct Client {
-    pub tcp_stream: Arc<RwLock<TcpStream>>,
     pub config: ConnectionConfig,
+    session: Arc<RwLock<Session>>,
+    /// Held while reconnecting, so the clones that find the connection broken reconnect once.
+    reconnecting: Arc<Mutex<()>>,
 }
 
-impl Client {
-    pub async fn new(config: ConnectionConfig) -> anyhow::Result<Self> {
-        let connection = ConnectionBuilder::new(&config);
-        let stream = connection.connect().await?;
+/// The connection currently used by the client and its handshake result.
+#[derive(Debug, Clone)]
+struct Session {
+    multiplexer: Multiplexer,
+    server_info: Option<ServerInfo>,
+}
 
+impl Client {
+    /// Opens the connection and performs the handshake (e.g. `AUTH`) once, before any command.
+    pub async fn new(config: ConnectionConfig) -> crate::Result<Self> {
+        let session = Self::open(&config).await?;
         Ok(Self {
-            tcp_stream: stream.clone(),
             config,
+            session: Arc::new(RwLock::new(session)),
+            reconnecting: Arc::new(Mutex::new(())),
         })
     }
 
-    async fn auth(&self) -> anyhow::Result<()> {
-        if self.config.username.is_some() || self.config.password.is_some() {
-            let stream = self.tcp_stream.clone();
-            let config = self.config.clone();
-            let command = CommandKind::Auth(AuthConfig {
-                username: config.username,
-                password: config.password,
-            })
-            .build()?;
-            let execute = Execute::new(stream).await;
-            let result = execute.send(&command).await?;
-            if let RespDataTypeValue::Error(kind, message) = result {
-                return Err(anyhow!(format!(
-                    "CLIENT_AUTH_ERROR: {:?}, {}",
-                    kind, message
-                )));
-            }
+    async fn open(config: &ConnectionConfig) -> crate::Result<Session> {
+        let (stream, server_info) = ConnectionBuilder::new(config).open().await?;
+        Ok(Session {
+            multiplexer: Multiplexer::with_limits(stream, config.parser_limits),
+            server_info,
+        })
+    }
+
+    fn session(&self) -> Session {
+        self.session
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Returns the multiplexer of the current connection.
+    pub fn multiplexer(&self) -> Multiplexer {
+        self.session().multiplexer
+    }
+
+    /// Returns the server details returned by `HELLO` during the last handshake.
+    pub fn server_info(&self) -> Option<ServerInfo> {
+        self.session().server_info
+    }
+
+    /// Returns true if the current connection is broken. With `reconnect` set, the next command
+    /// reopens it.
+    pub fn is_closed(&self) -> bool {
+        self.multiplexer().is_closed()
+    }
+
+    /// Sends the command within the timeouts of the config.
+    pub async fn send(&self, command: CommandKind) -> crate::Result<RespDataTypeValue> {
+        self.send_with_timeouts(command, self.config.timeouts())
+            .await
+    }
+
+    /// Sends the command within the given time limits instead of the ones of the config, e.g.
+    /// `Timeouts { response: Some(Duration::from_secs(30)), ..client.config.timeouts() }` for a
+    /// slow command. A slow reply fails with `Error::Timeout(TimeoutKind::Response)`.
+    /// A subscription command, e.g. `CommandKind::Custom("SUBSCRIBE", channels)`, returns the
+    /// confirmation of its last channel, the messages are received with `subscribe_pushes`.
+    pub async fn send_with_timeouts(
+        &self,
+        command: CommandKind,
+        timeouts: Timeouts,
+    ) -> crate::Result<RespDataTypeValue> {
+        let idempotent = command.is_idempotent();
+        let expected = vec![command.expected_replies()];
+        match self
+            .submit(command.build()?, expected, idempotent, timeouts)
+            .await?
+            .pop()
+        {
+            Some(result) => Ok(result),
+            None => Err(Error::UnexpectedReply("REPLY_NOT_FOUND".to_string())),
+        }
+    }
+
+    /// Sends the command and converts the reply, e.g. `client.query::<Vec<String>>(command)`.
+    /// An error reply is returned as `Error::Server`.
+    pub async fn query<T: FromRespValue>(&self, command: CommandKind) -> crate::Result<T> {
+        T::from_resp_value(self.send(command).await?)
+    }
+
+    /// Gets the value of a key, use an `Option` to get `None` when the key does not exist.
+    pub async fn get<T: FromRespValue>(&self, key: &str) -> crate::Result<T> {
+        self.query(CommandKind::Get(key.to_string())).await
+    }
+
+    pub async fn set(&self, key: &str, value: Value) -> crate::Result<()> {
+        self.query(CommandKind::Set(key.to_string(), value)).await
+    }
+
+    /// Deletes the keys and returns the number of keys that were removed.
+    pub async fn del(&self, keys: &[&str]) -> crate::Result<usize> {
+        let keys = keys.iter().map(|key| key.to_string()).collect();
+        self.query(CommandKind::Delete(keys)).await
+    }
+
+    /// Returns the number of keys that exist, a key given twice is counted twice.
+    pub async fn exists(&self, keys: &[&str]) -> crate::Result<usize> {
+        let keys = keys.iter().map(|key| key.to_string()).collect();
+        self.query(CommandKind::Exists(keys)).await
+    }
+
+    /// Increments the key by one and returns the new value.
+    pub async fn incr<T: FromRespValue>(&self, key: &str) -> crate::Result<T> {
+        self.query(CommandKind::Increment(key.to_string())).await
+    }
+
+    /// Increments the key and returns the new value.
+    pub async fn incr_by<T: FromRespValue>(&self, key: &str, value: u64) -> crate::Result<T> {
+        self.query(CommandKind::IncrementBy(key.to_string(), value))
+            .await
+    }
+
+    /// Decrements the key by one and returns the new value.
+    pub async fn decr<T: FromRespValue>(&self, key: &str) -> crate::Result<T> {
+        self.query(CommandKind::Decrement(key.to_string())).await
+    }
+
+    /// Decrements the key and returns the new value.
+    pub async fn decr_by<T: FromRespValue>(&self, key: &str, value: u64) -> crate::Result<T> {
+        self.query(CommandKind::DecrementBy(key.to_string(), value))
+            .await
+    }
+
+    /// Returns the keys matching the pattern.
+    pub async fn keys(&self, pattern: &str) -> crate::Result<Vec<String>> {
+        self.query(CommandKind::Keys(pattern.to_string())).await
+    }
+
+    /// Sets a timeout in seconds on the key.
+    /// Returns false when the key does not exist or the condition of `kind` is not met.
+    pub async fn expire(
+        &self,
+        key: &str,
+        seconds: u64,
+        kind: Option<ExpiryKind>,
+    ) -> crate::Result<bool> {
+        self.query(CommandKind::Expire(key.to_string(), seconds, kind))
+            .await
+    }
+
+    /// Returns the remaining time to live in seconds, -1 without a timeout and -2 without the key.
+    pub async fn ttl(&self, key: &str) -> crate::Result<i64> {
+        self.query(CommandKind::Ttl(key.to_string())).await
+    }
+
+    pub async fn ping(&self) -> crate::Result<String> {
+        self.query(CommandKind::Ping).await
+    }
+
+    /// Writes every command of the pipeline in one batch and returns the replies in order.
+    /// A subscription command has one reply per channel, see `CommandKind::expected_replies`.
+    pub async fn send_pipeline(
+        &self,
+        pipeline: &Pipeline,
+    ) -> crate::Result<Vec<RespDataTypeValue>> {
+        if pipeline.is_empty() {
+            return Ok(Vec::new());
         }
-        Ok(())
+        let commands = pipeline.build()?;
+        let expected = pipeline.expected_replies();
+        let idempotent = pipeline.is_idempotent();
+        self.submit(commands, expected, idempotent, self.config.timeouts())
+            .await
+    }
+
+    /// Subscribes to the push messages sent by the server over RESP3, e.g. pub/sub messages and
+    /// client-side caching invalidations. They are never returned as the reply to a command,
+    /// except the confirmations of the subscription commands.
+    /// The receiver is closed when the connection breaks, subscribe again after reconnecting.
+    pub fn subscribe_pushes(&self) -> broadcast::Receiver<RespDataTypeValue> {
+        self.multiplexer().subscribe()
     }
 
-    pub async fn send(&self, command: CommandKind) -> anyhow::Result<RespDataTypeValue> {
-        self.auth().await?;
-        let command = command.build()?;
-        let stream = self.tcp_stream.clone();
-        let execute = Execute::new(stream).await;
-        execute.send(&command).await
+    /// Submits encoded commands on the current connection, reopening it first when it is broken.
+    /// When it breaks during the call, it is reopened and the commands are sent again if they are
+    /// idempotent and `retry_idempotent` is set, otherwise the error is returned.
+    /// Parameters:
+    /// - commands - The encoded commands written back to back.
+    /// - expected - The replies of each command.
+    /// - idempotent - Whether the commands can be sent twice.
+    /// - timeouts - The time limits of each attempt.
+    async fn submit(
+        &self,
+        commands: Vec<u8>,
+        expected: Vec<ExpectedReplies>,
+        idempotent: bool,
+        timeouts: Timeouts,
+    ) -> crate::Result<Vec<RespDataTypeValue>> {
+        let Some(reconnect) = &self.config.reconnect else {
+            return self
+                .multiplexer()
+                .send_batch(commands, expected, timeouts)
+                .await;
+        };
+        let mut multiplexer = self.multiplexer();
+        if multiplexer.is_closed() {
+            multiplexer = self.reconnect(&multiplexer).await?;
+        }
+        let retry = (reconnect.retry_idempotent && idempotent)
+            .then(|| (commands.clone(), expected.clone()));
+        match multiplexer.send_batch(commands, expected, timeouts).await {
+            Err(err) if err.is_connection_error() => match retry {
+                Some((commands, expected)) => {
+                    let multiplexer = self.reconnect(&multiplexer).await?;
+                    multiplexer.send_batch(commands, expected, timeouts).await
+                }
+                // The command may have been executed, so it is not sent again, the next command
+                // reopens the connection.
+                None => Err(err),
+            },
+            result => result,
+        }
+    }
+
+    /// Reopens the connection with the backoff of the config and replays the handshake
+    /// (`AUTH`/`HELLO`, `SELECT`, client name).
+    /// Parameters:
+    /// - broken - The multiplexer of the broken connection.
+    ///   Returns:
+    /// - multiplexer - The multiplexer of the new connection, or of the one opened meanwhile by
+    ///   another clone.
+    async fn reconnect(&self, broken: &Multiplexer) -> crate::Result<Multiplexer> {
+        let _reconnecting = self.reconnecting.lock().await;
+        let current = self.multiplexer();
+        if !current.same_connection(broken) && !current.is_closed() {
+            return Ok(current);
+        }
+        let reconnect = self.config.reconnect.clone().unwrap_or_default();
+        let mut failures = 0;
+        loop {
+            match Self::open(&self.config).await {
+                Ok(session) => {
+                    let multiplexer = session.multiplexer.clone();
+                    *self.session.write().unwrap_or_else(|err| err.into_inner()) = session;
+                    return Ok(multiplexer);
+                }
+                Err(err) => {
+                    failures += 1;
+                    let retryable = err.is_connection_error()
+                        || err.is_timeout()
+                        || err.details().is_some_and(|details| details.is_retryable());
+                    if !retryable || reconnect.max_attempts.is_some_and(|max| failures >= max) {
+                        return Err(err);
+                    }
+                    tokio::time::sleep(reconnect.delay(failures)).await;
+                }
+            }
+        }
     }
 }
 
 #[cfg(test)]
 pub mod test_client {
-    use serde_json::Value;
-
     use super::*;
+    use crate::builder::resp_data_type::ParserLimits;
+    use crate::transport::reconnect::ReconnectConfig;
+    use crate::types::ProtocolVersion;
+    use crate::{ProtocolCode, TimeoutKind};
     #[tokio::test]
     async fn test_set_get() {
         let config = ConnectionConfig {
             address: "127.0.0.1:6379".to_string(),
             username: Some("myapp".to_string()),
             password: Some("password123".to_string()),
+            ..Default::default()
         };
         let client = Client::new(config).await;
         assert!(client.is_ok(), "{:#?}", client.err());
@@ -83,4 +307,554 @@ pub mod test_client {
             result.unwrap()
         );
     }
+
+    #[tokio::test]
+    async fn test_binary() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            ..Default::default()
+        };
+        let client = Client::new(config).await.unwrap();
+        let value: Vec<u8> = vec![0x00, 0xFF, 0x0D, 0x0A, 0xC3, 0x28];
+        let result = client
+            .send(CommandKind::SetBytes(
+                "myclientbinary".to_string(),
+                value.clone(),
+            ))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = client
+            .send(CommandKind::Custom(
+                "GET".to_string(),
+                vec![b"myclientbinary".to_vec()],
+            ))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(RespDataTypeValue::Bytes(value.clone()), result.unwrap());
+        let result = client.get::<bytes::Bytes>("myclientbinary").await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(value, result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_pipeline() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            ..Default::default()
+        };
+        let client = Client::new(config).await;
+        assert!(client.is_ok(), "{:#?}", client.err());
+        let client = client.unwrap();
+        let mut pipeline = Pipeline::new();
+        for i in 0..1000 {
+            pipeline.add(CommandKind::Set(
+                format!("mypipeline:{i}"),
+                Value::String(format!("value{i}")),
+            ));
+        }
+        for i in 0..1000 {
+            pipeline.add(CommandKind::Get(format!("mypipeline:{i}")));
+        }
+        let result = client.send_pipeline(&pipeline).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = result.unwrap();
+        assert_eq!(2000, result.len());
+        for (i, value) in result[1000..].iter().enumerate() {
+            assert_eq!(&RespDataTypeValue::String(format!("value{i}")), value);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shared_between_tasks() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            ..Default::default()
+        };
+        let client = Client::new(config).await;
+        assert!(client.is_ok(), "{:#?}", client.err());
+        let client = client.unwrap();
+        let mut handles = Vec::new();
+        for i in 0..50 {
+            let client = client.clone();
+            handles.push(tokio::spawn(async move {
+                let key = format!("mysharedclient:{i}");
+                client
+                    .send(CommandKind::Set(key.clone(), Value::from(i)))
+                    .await?;
+                client.send(CommandKind::Increment(key)).await
+            }));
+        }
+        for (i, handle) in handles.into_iter().enumerate() {
+            let result = handle.await.unwrap();
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(RespDataTypeValue::Integer(i as i64 + 1), result.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_error() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("wrongpassword".to_string()),
+            ..Default::default()
+        };
+        let client = Client::new(config).await;
+        assert!(client.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resp3() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            protocol: crate::types::ProtocolVersion::Resp3,
+            client_name: Some("myclient".to_string()),
+            ..Default::default()
+        };
+        let client = Client::new(config).await;
+        assert!(client.is_ok(), "{:#?}", client.err());
+        let client = client.unwrap();
+        assert_eq!(Some(3), client.server_info().map(|info| info.proto));
+        let result = client.send(CommandKind::Ping).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::String("PONG".to_string()),
+            result.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            protocol: ProtocolVersion::Resp3,
+            ..Default::default()
+        };
+        let subscriber = Client::new(config.clone()).await;
+        assert!(subscriber.is_ok(), "{:#?}", subscriber.err());
+        let subscriber = subscriber.unwrap();
+        let mut pushes = subscriber.subscribe_pushes();
+        let channels = vec![b"mynews".to_vec(), b"mysports".to_vec()];
+        // Each channel is confirmed by a push, they are the reply of the command.
+        let result = subscriber
+            .send(CommandKind::Custom(
+                "SUBSCRIBE".to_string(),
+                channels.clone(),
+            ))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::Push(vec![
+                RespDataTypeValue::String("subscribe".to_string()),
+                RespDataTypeValue::String("mysports".to_string()),
+                RespDataTypeValue::Integer(2),
+            ]),
+            result.unwrap()
+        );
+        let publisher = Client::new(ConnectionConfig {
+            protocol: ProtocolVersion::Resp2,
+            ..config
+        })
+        .await
+        .unwrap();
+        let result = publisher
+            .send(CommandKind::Custom(
+                "PUBLISH".to_string(),
+                vec![b"mynews".to_vec(), b"hello".to_vec()],
+            ))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let push = tokio::time::timeout(std::time::Duration::from_secs(1), pushes.recv()).await;
+        assert!(matches!(push, Ok(Ok(_))), "{push:#?}");
+        assert_eq!(
+            RespDataTypeValue::Push(vec![
+                RespDataTypeValue::String("message".to_string()),
+                RespDataTypeValue::String("mynews".to_string()),
+                RespDataTypeValue::String("hello".to_string()),
+            ]),
+            push.unwrap().unwrap()
+        );
+        // The later replies still match their commands.
+        let result = subscriber.send(CommandKind::Ping).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::String("PONG".to_string()),
+            result.unwrap()
+        );
+        let result = subscriber
+            .send(CommandKind::Raw(
+                "SUBSCRIBE myweather mytraffic".to_string(),
+            ))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = subscriber
+            .send(CommandKind::Custom("UNSUBSCRIBE".to_string(), channels))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::Push(vec![
+                RespDataTypeValue::String("unsubscribe".to_string()),
+                RespDataTypeValue::String("mysports".to_string()),
+                RespDataTypeValue::Integer(2),
+            ]),
+            result.unwrap()
+        );
+        // Without channels, every remaining subscription is confirmed.
+        let result = subscriber
+            .send(CommandKind::Custom("UNSUBSCRIBE".to_string(), vec![]))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::Push(vec![
+                RespDataTypeValue::String("unsubscribe".to_string()),
+                RespDataTypeValue::String("mytraffic".to_string()),
+                RespDataTypeValue::Integer(0),
+            ]),
+            result.unwrap()
+        );
+        let result = subscriber.send(CommandKind::Ping).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::String("PONG".to_string()),
+            result.unwrap()
+        );
+        assert!(pushes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parser_limits() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            parser_limits: ParserLimits {
+                max_bulk_length: 8,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = Client::new(config).await.unwrap();
+        let set_command = CommandKind::Set(
+            "myclientlimits".to_string(),
+            Value::String("longer than the limit".to_string()),
+        );
+        let result = client.send(set_command).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = client
+            .send(CommandKind::Get("myclientlimits".to_string()))
+            .await;
+        assert!(
+            matches!(
+                &result,
+                Err(Error::Protocol {
+                    code: ProtocolCode::MaxBulkLengthExceeded,
+                    ..
+                })
+            ),
+            "{:#?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_typed() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            ..Default::default()
+        };
+        let client = Client::new(config).await.unwrap();
+        let result = client.del(&["myclienttyped", "myclienttypedmissing"]).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = client.get::<Option<String>>("myclienttyped").await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(None, result.unwrap());
+        let result = client.incr_by::<i64>("myclienttyped", 41).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(41, result.unwrap());
+        let result = client.incr::<u32>("myclienttyped").await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(42, result.unwrap());
+        // The value is sent back as a bulk string.
+        let result = client.get::<i64>("myclienttyped").await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(42, result.unwrap());
+        let result = client
+            .exists(&["myclienttyped", "myclienttyped", "myclienttypedmissing"])
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(2, result.unwrap());
+        let result = client.expire("myclienttyped", 100, None).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert!(result.unwrap());
+        let result = client.ttl("myclienttyped").await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert!(result.unwrap() > 0);
+        let result = client.ping().await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!("PONG", result.unwrap());
+        let result = client
+            .set("myclienttyped", Value::String("abc".to_string()))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = client.incr::<i64>("myclienttyped").await;
+        assert!(matches!(result, Err(Error::Server(_, _))), "{:#?}", result);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::UnixListener;
+
+        let path = std::env::temp_dir().join(format!("valkeyree-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 1024];
+            while let Ok(read) = stream.read(&mut buffer).await {
+                if read == 0 || stream.write_all(b"+PONG\r\n").await.is_err() {
+                    break;
+                }
+            }
+        });
+        let client = Client::new(ConnectionConfig {
+            address: path.to_string_lossy().to_string(),
+            ..Default::default()
+        })
+        .await;
+        assert!(client.is_ok(), "{:#?}", client.err());
+        let result = client.unwrap().ping().await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!("PONG", result.unwrap());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_unix_socket_not_found() {
+        let client = Client::new(ConnectionConfig {
+            address: "/nonexistent/valkey.sock".to_string(),
+            ..Default::default()
+        })
+        .await;
+        assert!(client.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timeouts() {
+        // The server accepts the connection but never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        });
+        let client = Client::new(ConnectionConfig {
+            address,
+            response_timeout: Some(std::time::Duration::from_millis(50)),
+            ..Default::default()
+        })
+        .await;
+        assert!(client.is_ok(), "{:#?}", client.err());
+        let client = client.unwrap();
+        let result = client.ping().await;
+        assert!(result.as_ref().is_err_and(Error::is_timeout), "{result:#?}");
+        let timeouts = Timeouts {
+            response: Some(std::time::Duration::from_millis(100)),
+            ..client.config.timeouts()
+        };
+        let result = client.send_with_timeouts(CommandKind::Ping, timeouts).await;
+        assert!(
+            matches!(&result, Err(Error::Timeout(TimeoutKind::Response))),
+            "{result:#?}"
+        );
+    }
+
+    /// Starts a server that drops its first connection on the first command, without replying.
+    /// The later connections answer `PING` with `PONG` and any other command with `1`.
+    /// Returns the address and the number of connections accepted.
+    async fn flaky_server() -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::Ordering;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let connections = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let index = accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buffer = [0u8; 1024];
+                    while let Ok(read) = stream.read(&mut buffer).await {
+                        if read == 0 || index == 0 {
+                            break;
+                        }
+                        let reply: &[u8] = match buffer[..read].windows(4).any(|w| w == b"PING") {
+                            true => b"+PONG\r\n",
+                            false => b":1\r\n",
+                        };
+                        if stream.write_all(reply).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        (address, connections)
+    }
+
+    #[tokio::test]
+    async fn test_reconnect() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            protocol: ProtocolVersion::Resp3,
+            client_name: Some("myclient".to_string()),
+            database: Some(1),
+            reconnect: Some(ReconnectConfig::default()),
+            ..Default::default()
+        };
+        let client = Client::new(config).await;
+        assert!(client.is_ok(), "{:#?}", client.err());
+        let client = client.unwrap();
+        let before = client.multiplexer();
+        // The server closes the connection after QUIT.
+        let result = client
+            .send(CommandKind::Custom("QUIT".to_string(), vec![]))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = client.ping().await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!("PONG", result.unwrap());
+        assert!(!client.multiplexer().same_connection(&before));
+        // The handshake was replayed on the new connection.
+        assert_eq!(Some(3), client.server_info().map(|info| info.proto));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_retry() {
+        use std::sync::atomic::Ordering;
+
+        let (address, connections) = flaky_server().await;
+        let client = Client::new(ConnectionConfig {
+            address,
+            reconnect: Some(ReconnectConfig::default()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        // PING is idempotent, so it is sent again on the new connection.
+        let result = client.ping().await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!("PONG", result.unwrap());
+        assert_eq!(2, connections.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_no_retry() {
+        use std::sync::atomic::Ordering;
+
+        let (address, connections) = flaky_server().await;
+        let client = Client::new(ConnectionConfig {
+            address,
+            reconnect: Some(ReconnectConfig::default()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        // INCR may have been executed before the connection broke, so it is not sent again.
+        let result = client.incr::<i64>("mycounter").await;
+        assert!(
+            result.as_ref().is_err_and(Error::is_connection_error),
+            "{result:#?}"
+        );
+        while !client.is_closed() {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        // The next command reopens the connection.
+        let result = client.incr::<i64>("mycounter").await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(1, result.unwrap());
+        assert_eq!(2, connections.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_limit_exceeded() {
+        let config = ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            reconnect: Some(ReconnectConfig::default()),
+            ..Default::default()
+        };
+        let client = Client::new(config.clone()).await.unwrap();
+        let result = client
+            .send(CommandKind::Set(
+                "myoversized".to_string(),
+                Value::String("hello!".to_string()),
+            ))
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let client = Client::new(ConnectionConfig {
+            parser_limits: ParserLimits {
+                max_bulk_length: 5,
+                ..Default::default()
+            },
+            ..config
+        })
+        .await
+        .unwrap();
+        let before = client.multiplexer();
+        // GET is idempotent, but the same reply would exceed the limits again, so it is not sent
+        // again on a new connection.
+        let result = client
+            .send(CommandKind::Get("myoversized".to_string()))
+            .await;
+        assert!(
+            result.as_ref().is_err_and(Error::is_limit_exceeded),
+            "{result:#?}"
+        );
+        assert!(!result.as_ref().is_err_and(Error::is_connection_error));
+        assert!(client.multiplexer().same_connection(&before));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_gives_up() {
+        // The server closes the connection and stops listening.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let _ = listener.accept().await;
+        });
+        let client = Client::new(ConnectionConfig {
+            address,
+            reconnect: Some(ReconnectConfig {
+                max_attempts: Some(3),
+                initial_delay: std::time::Duration::from_millis(10),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let result = client.ping().await;
+        assert!(
+            result.as_ref().is_err_and(Error::is_connection_error),
+            "{result:#?}"
+        );
+    }
 }
@@ -1,49 +1,156 @@
-use crate::builder::resp_data_type::RespParser;
+use crate::builder::resp_data_type::{ParserLimits, RespDecoder};
+use crate::transport::stream::{BoxedStream, Stream};
+use crate::transport::timeouts::{Timeouts, within};
 use crate::types::RespDataTypeValue;
-use anyhow::anyhow;
+use crate::{Error, TimeoutKind};
+use bytes::BytesMut;
 use std::sync::Arc;
-use tokio::io::AsyncWriteExt;
-use tokio::net::TcpStream;
-use tokio::sync::RwLock;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{Mutex, RwLock};
+use tokio_util::codec::Decoder;
+
+/// The minimum number of bytes reserved before each read from the stream.
+const READ_BUFFER_SIZE: usize = 4096;
+
+/// The bytes read from a stream that were not decoded yet, and the decoder they are fed to.
+/// It lives as long as the stream, so the bytes received after the last expected reply are
+/// decoded by the next call instead of being dropped.
+#[derive(Debug, Default)]
+pub struct ReadBuffer {
+    data: BytesMut,
+    decoder: RespDecoder,
+}
+
+impl ReadBuffer {
+    /// Creates an empty buffer whose replies are decoded with the given limits.
+    pub fn with_limits(limits: ParserLimits) -> Self {
+        Self {
+            data: BytesMut::new(),
+            decoder: RespDecoder::with_limits(limits),
+        }
+    }
+
+    /// Returns the bytes that were read but not decoded yet.
+    pub fn into_bytes(self) -> BytesMut {
+        self.data
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Execute {
-    pub stream: Arc<RwLock<TcpStream>>,
+    pub stream: Arc<RwLock<BoxedStream>>,
+    /// The time limits of every call, none by default.
+    pub timeouts: Timeouts,
+    buffer: Arc<Mutex<ReadBuffer>>,
 }
 
 impl Execute {
-    pub async fn new(stream: Arc<RwLock<TcpStream>>) -> Self {
+    pub async fn new(stream: Arc<RwLock<BoxedStream>>) -> Self {
         Self {
             stream: stream.clone(),
+            timeouts: Timeouts::default(),
+            buffer: Arc::new(Mutex::new(ReadBuffer::default())),
+        }
+    }
+
+    /// Sets the time limits of every call.
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Sets the limits the replies are decoded with, the defaults of `ParserLimits` otherwise.
+    /// It is meant to be called before the first command is sent.
+    pub fn with_limits(mut self, limits: ParserLimits) -> Self {
+        self.buffer = Arc::new(Mutex::new(ReadBuffer::with_limits(limits)));
+        self
+    }
+
+    pub async fn send(&self, command: &[u8]) -> crate::Result<RespDataTypeValue> {
+        match self.send_many(command, 1).await?.pop() {
+            Some(result) => Ok(result),
+            None => Err(Error::UnexpectedReply("REPLY_NOT_FOUND".to_string())),
         }
     }
-    pub async fn send(&self, command: &String) -> anyhow::Result<RespDataTypeValue> {
-        let command = command.as_bytes();
+
+    /// Writes a batch of encoded commands at once and reads one reply per command.
+    /// Parameters:
+    /// - commands - The encoded commands written back to back.
+    /// - count - The number of commands in the batch.
+    pub async fn send_many(
+        &self,
+        commands: &[u8],
+        count: usize,
+    ) -> crate::Result<Vec<RespDataTypeValue>> {
         let stream = self.stream.clone();
         let mut connection = stream.write().await;
-        connection.write_all(command).await?;
-        let mut data: Vec<u8> = Vec::new();
-        loop {
-            connection.readable().await?;
-            let mut buf = [0; 32];
-            match connection.try_read(&mut buf) {
-                Ok(0) => break,
-                Ok(size) => {
-                    for b in buf[..size].iter() {
-                        data.push(*b);
-                    }
-                }
-                Err(err) => {
-                    if err.kind() == tokio::io::ErrorKind::WouldBlock {
-                        break;
-                    }
-                    return Err(anyhow!(err.to_string()));
+        let mut buffer = self.buffer.lock().await;
+        let result = Self::exchange(
+            connection.as_mut(),
+            &mut buffer,
+            commands,
+            count,
+            self.timeouts,
+        )
+        .await;
+        if let Err(Error::Timeout(_)) = result {
+            // A late reply would be read as the reply of the next command, so the stream is
+            // closed and the later calls fail.
+            let _ = connection.shutdown().await;
+        }
+        result
+    }
+
+    /// Writes the encoded commands to a stream owned by the caller and reads one reply per command.
+    /// Push messages are not replies, so they are skipped, except the subscription confirmations.
+    /// Parameters:
+    /// - connection - The stream.
+    /// - buffer - The bytes read from the stream by the previous calls, and the limits of the
+    ///   replies. The bytes following the replies are left in it.
+    /// - commands - The encoded commands written back to back.
+    /// - count - The number of commands in the batch.
+    /// - timeouts - The time limits to write the commands and to read the replies.
+    pub async fn exchange(
+        connection: &mut dyn Stream,
+        buffer: &mut ReadBuffer,
+        commands: &[u8],
+        count: usize,
+        timeouts: Timeouts,
+    ) -> crate::Result<Vec<RespDataTypeValue>> {
+        within(timeouts.write, TimeoutKind::Write, async {
+            Ok(connection.write_all(commands).await?)
+        })
+        .await?;
+        within(
+            timeouts.response,
+            TimeoutKind::Response,
+            Self::receive(connection, buffer, count),
+        )
+        .await
+    }
+
+    /// Reads one reply per command, skipping the push messages but not the subscription
+    /// confirmations.
+    async fn receive(
+        connection: &mut dyn Stream,
+        buffer: &mut ReadBuffer,
+        count: usize,
+    ) -> crate::Result<Vec<RespDataTypeValue>> {
+        let ReadBuffer { data, decoder } = buffer;
+        let mut results: Vec<RespDataTypeValue> = Vec::with_capacity(count);
+        while results.len() < count {
+            if let Some(result) = decoder.decode(data)? {
+                if !result.without_attributes().is_push() || result.is_subscription_confirmation() {
+                    results.push(result);
                 }
+                continue;
+            }
+            data.reserve(READ_BUFFER_SIZE);
+            if connection.read_buf(data).await? == 0 {
+                return Err(Error::ConnectionClosed);
             }
         }
-        let mut parser = RespParser::new(&data);
-        let result = parser.parse()?;
-        Ok(result)
+        Ok(results)
     }
 }
 
@@ -52,26 +159,19 @@ pub mod test_execute {
     use serde_json::Value;
 
     use super::*;
-    use crate::builder::commands::AuthConfig;
+    use crate::ProtocolCode;
+    use crate::builder::commands::{AuthConfig, HelloConfig};
     use crate::transport::connection::{ConnectionBuilder, ConnectionConfig};
-    use crate::types::command_kind::CommandKind;
     use crate::types::ExpiryKind;
-
-    async fn auth(execute: &Execute) -> anyhow::Result<RespDataTypeValue> {
-        let auth_command = CommandKind::Auth(AuthConfig {
-            username: Some("myapp".to_string()),
-            password: Some("password123".to_string()),
-        })
-        .build();
-        execute.send(&auth_command.unwrap()).await
-    }
+    use crate::types::command_kind::CommandKind;
 
     #[tokio::test]
     async fn test_auth() {
         let connection = ConnectionBuilder::new(&ConnectionConfig {
             address: "127.0.0.1:6379".to_string(),
-            username: Some("myapp".to_string()),
-            password: Some("zxczxc123".to_string()),
+            username: None,
+            password: None,
+            ..Default::default()
         })
         .connect()
         .await;
@@ -93,8 +193,9 @@ pub mod test_execute {
     async fn test_auth_error() {
         let connection = ConnectionBuilder::new(&ConnectionConfig {
             address: "127.0.0.1:6379".to_string(),
-            username: Some("myapp".to_string()),
-            password: Some("zxczxc123".to_string()),
+            username: None,
+            password: None,
+            ..Default::default()
         })
         .connect()
         .await;
@@ -114,13 +215,13 @@ pub mod test_execute {
             address: "127.0.0.1:6379".to_string(),
             username: Some("myapp".to_string()),
             password: Some("password123".to_string()),
+            ..Default::default()
         })
         .connect()
         .await;
         assert!(connection.is_ok(), "{:#?}", connection.err());
         let stream = connection.unwrap();
         let execute = Execute::new(stream).await;
-        auth(&execute).await.unwrap();
         let get_command = CommandKind::Get("idontexistkey".to_string()).build();
         assert!(get_command.is_ok(), "{:#?}", get_command.err());
         let result = execute.send(&get_command.unwrap()).await;
@@ -134,13 +235,13 @@ pub mod test_execute {
             address: "127.0.0.1:6379".to_string(),
             username: Some("myapp".to_string()),
             password: Some("password123".to_string()),
+            ..Default::default()
         })
         .connect()
         .await;
         assert!(connection.is_ok(), "{:#?}", connection.err());
         let stream = connection.unwrap();
         let execute = Execute::new(stream).await;
-        auth(&execute).await.unwrap();
         let set_command =
             CommandKind::Set("testmykey".to_string(), Value::String("hello".to_string())).build();
         assert!(set_command.is_ok(), "{:#?}", set_command.err());
@@ -158,7 +259,7 @@ pub mod test_execute {
         if let RespDataTypeValue::Integer(n) = result.unwrap() {
             assert!(n > 0);
         } else {
-            assert!(false);
+            panic!("expected an integer reply");
         }
     }
 
@@ -168,13 +269,13 @@ pub mod test_execute {
             address: "127.0.0.1:6379".to_string(),
             username: Some("myapp".to_string()),
             password: Some("password123".to_string()),
+            ..Default::default()
         })
         .connect()
         .await;
         assert!(connection.is_ok(), "{:#?}", connection.err());
         let stream = connection.unwrap();
         let execute = Execute::new(stream).await;
-        auth(&execute).await.unwrap();
         let set_command =
             CommandKind::Set("deleteme".to_string(), Value::String("hello".to_string())).build();
         assert!(set_command.is_ok(), "{:#?}", set_command.err());
@@ -193,6 +294,7 @@ pub mod test_execute {
             address: "127.0.0.1:6379".to_string(),
             username: None,
             password: None,
+            ..Default::default()
         })
         .connect()
         .await;
@@ -212,13 +314,13 @@ pub mod test_execute {
             address: "127.0.0.1:6379".to_string(),
             username: Some("myapp".to_string()),
             password: Some("password123".to_string()),
+            ..Default::default()
         })
         .connect()
         .await;
         assert!(connection.is_ok(), "{:#?}", connection.err());
         let stream = connection.unwrap();
         let execute = Execute::new(stream).await;
-        auth(&execute).await.unwrap();
         let ping_command = CommandKind::Ping.build();
         assert!(ping_command.is_ok(), "{:#?}", ping_command.err());
         let command = ping_command.unwrap();
@@ -235,14 +337,14 @@ pub mod test_execute {
             address: "127.0.0.1:6379".to_string(),
             username: Some("myapp".to_string()),
             password: Some("password123".to_string()),
+            ..Default::default()
         })
         .connect()
         .await;
         assert!(connection.is_ok(), "{:#?}", connection.err());
         let stream = connection.unwrap();
         let execute = Execute::new(stream).await;
-        auth(&execute).await.unwrap();
-        let hello_command = CommandKind::Hello.build();
+        let hello_command = CommandKind::Hello(HelloConfig::default()).build();
         assert!(hello_command.is_ok(), "{:#?}", hello_command.err());
         let command = hello_command.unwrap();
         let result = execute.send(&command).await;
@@ -255,13 +357,13 @@ pub mod test_execute {
             address: "127.0.0.1:6379".to_string(),
             username: Some("myapp".to_string()),
             password: Some("password123".to_string()),
+            ..Default::default()
         })
         .connect()
         .await;
         assert!(connection.is_ok(), "{:#?}", connection.err());
         let stream = connection.unwrap();
         let execute = Execute::new(stream).await;
-        auth(&execute).await.unwrap();
         let expire_command =
             CommandKind::Expire("expireme".to_string(), 10, Some(ExpiryKind::Nx)).build();
         assert!(expire_command.is_ok(), "{:#?}", expire_command.err());
@@ -302,13 +404,13 @@ pub mod test_execute {
             address: "127.0.0.1:6379".to_string(),
             username: Some("myapp".to_string()),
             password: Some("password123".to_string()),
+            ..Default::default()
         })
         .connect()
         .await;
         assert!(connection.is_ok(), "{:#?}", connection.err());
         let stream = connection.unwrap();
         let execute = Execute::new(stream).await;
-        auth(&execute).await.unwrap();
         let command = CommandKind::Increment("incrdecr".to_string()).build();
         assert!(command.is_ok(), "{:#?}", command.err());
         let result = execute.send(&command.unwrap()).await;
@@ -330,23 +432,173 @@ pub mod test_execute {
         assert!(result.is_ok(), "{:#?}", result.is_err());
         assert!(result.unwrap().is_integer());
     }
+    #[tokio::test]
+    async fn test_large_value() {
+        let connection = ConnectionBuilder::new(&ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            ..Default::default()
+        })
+        .connect()
+        .await;
+        assert!(connection.is_ok(), "{:#?}", connection.err());
+        let stream = connection.unwrap();
+        let execute = Execute::new(stream).await;
+        // Large enough to span many TCP segments and reads.
+        let value = "v".repeat(512 * 1024);
+        let set_command =
+            CommandKind::Set("mylargekey".to_string(), Value::String(value.clone())).build();
+        assert!(set_command.is_ok(), "{:#?}", set_command.err());
+        let result = execute.send(&set_command.unwrap()).await;
+        assert!(result.is_ok(), "{:#?}", result.is_err());
+        let get_command = CommandKind::Get("mylargekey".to_string()).build();
+        assert!(get_command.is_ok(), "{:#?}", get_command.err());
+        let result = execute.send(&get_command.unwrap()).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(RespDataTypeValue::String(value), result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_large_array() {
+        // About 1.7 MB of small elements, received in reads of at most 4 KB.
+        let (mut stream, mut server) = tokio::io::duplex(4096);
+        let length = 200_000;
+        let mut reply = format!("*{length}\r\n").into_bytes();
+        for i in 0..length {
+            reply.extend_from_slice(format!(":{i}\r\n").as_bytes());
+        }
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 1024];
+            if let Ok(read) = server.read(&mut buffer).await
+                && read > 0
+            {
+                let _ = server.write_all(&reply).await;
+            }
+        });
+        let command = CommandKind::Keys("*".to_string()).build().unwrap();
+        let mut buffer = ReadBuffer::default();
+        let result =
+            Execute::exchange(&mut stream, &mut buffer, &command, 1, Timeouts::default()).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let expected = (0..length).map(RespDataTypeValue::Integer).collect();
+        assert_eq!(vec![RespDataTypeValue::Array(expected)], result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_leftover() {
+        // The server sends both replies at once, after the first command.
+        let (stream, mut server) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 1024];
+            if let Ok(read) = server.read(&mut buffer).await
+                && read > 0
+                && server.write_all(b"+OK\r\n+PONG\r\n").await.is_ok()
+            {
+                while let Ok(read) = server.read(&mut buffer).await
+                    && read > 0
+                {}
+            }
+        });
+        let stream: BoxedStream = Box::new(stream);
+        let execute = Execute::new(Arc::new(RwLock::new(stream))).await;
+        let command = CommandKind::Ping.build().unwrap();
+        let result = execute.send(&command).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(RespDataTypeValue::String("OK".to_string()), result.unwrap());
+        // The second reply was read with the first one and is kept for the next call.
+        let result = execute.send(&command).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::String("PONG".to_string()),
+            result.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_limits() {
+        let (stream, mut server) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 1024];
+            while let Ok(read) = server.read(&mut buffer).await {
+                if read == 0 || server.write_all(b"$6\r\nhello!\r\n").await.is_err() {
+                    break;
+                }
+            }
+        });
+        let stream: BoxedStream = Box::new(stream);
+        let execute = Execute::new(Arc::new(RwLock::new(stream)))
+            .await
+            .with_limits(ParserLimits {
+                max_bulk_length: 5,
+                ..Default::default()
+            });
+        let result = execute
+            .send(&CommandKind::Get("mykey".to_string()).build().unwrap())
+            .await;
+        assert!(
+            matches!(
+                &result,
+                Err(Error::Protocol {
+                    code: ProtocolCode::MaxBulkLengthExceeded,
+                    ..
+                })
+            ),
+            "{result:#?}"
+        );
+    }
+
     #[tokio::test]
     async fn test_keys() {
         let connection = ConnectionBuilder::new(&ConnectionConfig {
             address: "127.0.0.1:6379".to_string(),
             username: Some("myapp".to_string()),
             password: Some("password123".to_string()),
+            ..Default::default()
         })
         .connect()
         .await;
         assert!(connection.is_ok(), "{:#?}", connection.err());
         let stream = connection.unwrap();
         let execute = Execute::new(stream).await;
-        auth(&execute).await.unwrap();
         let command = CommandKind::Keys("*".to_string()).build();
         assert!(command.is_ok(), "{:#?}", command.err());
         let result = execute.send(&command.unwrap()).await;
         assert!(result.is_ok(), "{:#?}", result.is_err());
         assert!(result.unwrap().is_array());
     }
+
+    #[tokio::test]
+    async fn test_response_timeout() {
+        // The server answers the first command after a delay.
+        let (stream, mut server) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 1024];
+            while let Ok(read) = server.read(&mut buffer).await {
+                if read == 0 {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                if server.write_all(b"+PONG\r\n").await.is_err() {
+                    break;
+                }
+            }
+        });
+        let stream: BoxedStream = Box::new(stream);
+        let execute = Execute::new(Arc::new(RwLock::new(stream)))
+            .await
+            .with_timeouts(Timeouts {
+                response: Some(std::time::Duration::from_millis(50)),
+                ..Default::default()
+            });
+        let command = CommandKind::Ping.build().unwrap();
+        let result = execute.send(&command).await;
+        assert!(
+            matches!(&result, Err(Error::Timeout(TimeoutKind::Response))),
+            "{result:#?}"
+        );
+        // The stream was closed, so the late reply is never read as the next reply.
+        let result = execute.send(&command).await;
+        assert!(result.is_err(), "{result:#?}");
+    }
 }
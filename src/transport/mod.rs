@@ -1,4 +1,21 @@
 pub mod client;
 pub mod connection;
 pub mod execute;
+pub mod multiplexer;
+pub mod pipeline;
+pub mod pool;
+pub mod reconnect;
+pub mod stream;
+pub mod timeouts;
+#[cfg(feature = "tls")]
+pub mod tls;
+pub mod url;
 pub use client::Client;
+pub use multiplexer::Multiplexer;
+pub use pipeline::Pipeline;
+pub use pool::{Pool, PoolConfig, PooledClient};
+pub use reconnect::ReconnectConfig;
+pub use stream::{BoxedStream, Stream};
+pub use timeouts::Timeouts;
+#[cfg(feature = "tls")]
+pub use tls::TlsConfig;
@@ -0,0 +1,716 @@
+use crate::builder::resp_data_type::{ParserLimits, RespDecoder};
+use crate::transport::stream::{BoxedStream, Stream};
+use crate::transport::timeouts::{Timeouts, within};
+use crate::types::{ExpectedReplies, RespDataTypeValue};
+use crate::{Error, TimeoutKind};
+use bytes::BytesMut;
+use std::collections::{BTreeSet, VecDeque};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio_util::codec::Decoder;
+
+/// The minimum number of bytes reserved before each read from the stream.
+const READ_BUFFER_SIZE: usize = 4096;
+
+/// The number of push messages kept for a subscriber that falls behind.
+const PUSH_CHANNEL_CAPACITY: usize = 1024;
+
+type Reply = oneshot::Sender<crate::Result<Vec<RespDataTypeValue>>>;
+
+/// A batch of encoded commands submitted by a caller.
+#[derive(Debug)]
+struct Request {
+    commands: Vec<u8>,
+    expected: Vec<ExpectedReplies>,
+    write_timeout: Option<Duration>,
+    reply: Reply,
+}
+
+/// A batch that is written to the server and is waiting for its replies.
+#[derive(Debug)]
+struct Pending {
+    /// The replies still expected, one entry per command.
+    expected: VecDeque<ExpectedReplies>,
+    results: Vec<RespDataTypeValue>,
+    reply: Reply,
+}
+
+/// What the writer task tells the reader task.
+#[derive(Debug)]
+enum Written {
+    /// A batch about to be written.
+    Batch(Pending),
+    /// The write of the last batches failed, the connection is closed.
+    Failed(Error, usize),
+}
+
+/// The channels the connection is subscribed to, kept from the confirmations so a bare
+/// `UNSUBSCRIBE` is known to be fully answered once none is left.
+#[derive(Debug, Default)]
+struct Subscriptions {
+    /// The channels, the patterns and the shard channels.
+    channels: [BTreeSet<Vec<u8>>; 3],
+}
+
+impl Subscriptions {
+    /// Records a confirmation and returns the number of subscriptions of its family left.
+    /// Parameters:
+    /// - kind - The kind of the confirmation, e.g. `subscribe` or `punsubscribe`.
+    /// - channel - The channel, `None` when there was no subscription to remove.
+    fn record(&mut self, kind: &[u8], channel: Option<&[u8]>) -> usize {
+        let (family, subscribe) = match kind.to_ascii_lowercase().as_slice() {
+            b"subscribe" => (0, true),
+            b"unsubscribe" => (0, false),
+            b"psubscribe" => (1, true),
+            b"punsubscribe" => (1, false),
+            b"ssubscribe" => (2, true),
+            _ => (2, false),
+        };
+        let channels = &mut self.channels[family];
+        match channel {
+            Some(channel) if subscribe => {
+                channels.insert(channel.to_vec());
+            }
+            Some(channel) => {
+                channels.remove(channel);
+            }
+            None => {}
+        }
+        channels.len()
+    }
+}
+
+/// Shares one connection between many tasks.
+/// A background writer task owns the write half of the socket and writes the submitted commands,
+/// coalescing whatever is queued into a single write. A background reader task owns the read half
+/// and hands the replies back in the same order the commands were written (FIFO).
+/// Push messages are not replies to any command, so the reader sends them to the push subscribers.
+/// The pushes confirming `SUBSCRIBE`, `UNSUBSCRIBE` and their variants are the replies of these
+/// commands, one per channel, but only as many as the waiting commands expect; the ones the
+/// server sends on its own, e.g. `sunsubscribe` when a slot moves, go to the push subscribers.
+/// Cloning a `Multiplexer` is cheap and every clone submits to the same connection.
+#[derive(Debug, Clone)]
+pub struct Multiplexer {
+    sender: mpsc::UnboundedSender<Request>,
+    pushes: broadcast::Sender<RespDataTypeValue>,
+}
+
+impl Multiplexer {
+    /// Takes ownership of the stream and spawns the writer and reader tasks.
+    /// The tasks stop once every clone of the multiplexer is dropped or the connection is closed.
+    pub fn new(stream: impl Stream + 'static) -> Self {
+        Self::with_limits(stream, ParserLimits::default())
+    }
+
+    /// Same as `new`, the replies are decoded with the given limits.
+    /// A reply exceeding them fails every waiting caller and closes the connection, since the
+    /// rest of the stream can't be trusted.
+    pub fn with_limits(stream: impl Stream + 'static, limits: ParserLimits) -> Self {
+        let stream: BoxedStream = Box::new(stream);
+        let (reader, writer) = tokio::io::split(stream);
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (written_sender, written_receiver) = mpsc::unbounded_channel();
+        let (pushes, _) = broadcast::channel(PUSH_CHANNEL_CAPACITY);
+        tokio::spawn(Self::write_loop(writer, receiver, written_sender));
+        tokio::spawn(Self::read_loop(
+            reader,
+            written_receiver,
+            pushes.clone(),
+            RespDecoder::with_limits(limits),
+        ));
+        Self { sender, pushes }
+    }
+
+    /// Submits an encoded command and waits for its reply.
+    pub async fn send(&self, command: Vec<u8>) -> crate::Result<RespDataTypeValue> {
+        self.send_with_timeouts(command, Timeouts::default()).await
+    }
+
+    /// Same as `send`, within the given time limits.
+    pub async fn send_with_timeouts(
+        &self,
+        command: Vec<u8>,
+        timeouts: Timeouts,
+    ) -> crate::Result<RespDataTypeValue> {
+        match self
+            .send_many_with_timeouts(command, 1, timeouts)
+            .await?
+            .pop()
+        {
+            Some(result) => Ok(result),
+            None => Err(Error::UnexpectedReply("REPLY_NOT_FOUND".to_string())),
+        }
+    }
+
+    /// Submits a batch of encoded commands and waits for one reply per command.
+    /// The batch is written as a whole, so its commands are never interleaved with others.
+    /// Parameters:
+    /// - commands - The encoded commands written back to back.
+    /// - count - The number of commands in the batch.
+    pub async fn send_many(
+        &self,
+        commands: Vec<u8>,
+        count: usize,
+    ) -> crate::Result<Vec<RespDataTypeValue>> {
+        self.send_many_with_timeouts(commands, count, Timeouts::default())
+            .await
+    }
+
+    /// Same as `send_many`, within the given time limits.
+    pub async fn send_many_with_timeouts(
+        &self,
+        commands: Vec<u8>,
+        count: usize,
+        timeouts: Timeouts,
+    ) -> crate::Result<Vec<RespDataTypeValue>> {
+        self.send_batch(commands, vec![ExpectedReplies::One; count], timeouts)
+            .await
+    }
+
+    /// Submits a batch of encoded commands and waits for their replies, e.g. one confirmation per
+    /// channel for a subscription command, see `CommandKind::expected_replies`.
+    /// A caller whose reply times out stops waiting, the reply is discarded when it arrives and the
+    /// connection stays usable. A write that times out closes the connection.
+    /// Parameters:
+    /// - commands - The encoded commands written back to back.
+    /// - expected - The replies of each command, in the same order.
+    /// - timeouts - The time limits of the batch.
+    pub async fn send_batch(
+        &self,
+        commands: Vec<u8>,
+        expected: Vec<ExpectedReplies>,
+        timeouts: Timeouts,
+    ) -> crate::Result<Vec<RespDataTypeValue>> {
+        let (reply, receiver) = oneshot::channel();
+        let request = Request {
+            commands,
+            expected,
+            write_timeout: timeouts.write,
+            reply,
+        };
+        if self.sender.send(request).is_err() {
+            return Err(Error::ConnectionClosed);
+        }
+        within(timeouts.response, TimeoutKind::Response, async {
+            match receiver.await {
+                Ok(result) => result,
+                Err(_) => Err(Error::ConnectionClosed),
+            }
+        })
+        .await
+    }
+
+    /// Subscribes to the push messages (`RespDataTypeValue::Push`) received from now on.
+    /// A subscriber that falls more than `PUSH_CHANNEL_CAPACITY` messages behind skips the oldest ones.
+    pub fn subscribe(&self) -> broadcast::Receiver<RespDataTypeValue> {
+        self.pushes.subscribe()
+    }
+
+    /// Returns true if the background tasks have stopped.
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Returns true if both multiplexers share the same connection.
+    pub fn same_connection(&self, other: &Multiplexer) -> bool {
+        self.sender.same_channel(&other.sender)
+    }
+
+    async fn write_loop(
+        mut writer: WriteHalf<BoxedStream>,
+        mut receiver: mpsc::UnboundedReceiver<Request>,
+        written_sender: mpsc::UnboundedSender<Written>,
+    ) {
+        loop {
+            // Once the reader stops the connection is unusable, so the writer stops too and the
+            // multiplexer reports itself closed instead of writing to a dead socket.
+            let request = tokio::select! {
+                biased;
+                _ = written_sender.closed() => break,
+                request = receiver.recv() => match request {
+                    Some(request) => request,
+                    None => break,
+                },
+            };
+            let mut requests = vec![request];
+            while let Ok(request) = receiver.try_recv() {
+                requests.push(request);
+            }
+            // The coalesced requests share the write, so the shortest time limit applies.
+            let write_timeout = requests
+                .iter()
+                .filter_map(|request| request.write_timeout)
+                .min();
+            let count = requests.len();
+            let mut data: Vec<u8> = Vec::new();
+            // The batches are handed to the reader before they are written, so it knows which
+            // replies are expected when they arrive, e.g. whether a confirmation answers a command.
+            for request in requests {
+                data.extend_from_slice(&request.commands);
+                let pending = Pending {
+                    expected: request.expected.into(),
+                    results: Vec::new(),
+                    reply: request.reply,
+                };
+                if let Err(err) = written_sender.send(Written::Batch(pending))
+                    && let Written::Batch(pending) = err.0
+                {
+                    let _ = pending.reply.send(Err(Error::ConnectionClosed));
+                }
+            }
+            let write = async { Ok(writer.write_all(&data).await?) };
+            if let Err(err) = within(write_timeout, TimeoutKind::Write, write).await {
+                let _ = written_sender.send(Written::Failed(err, count));
+                break;
+            }
+        }
+    }
+
+    /// Reads the socket even when no command is waiting, so push messages are delivered as they arrive.
+    async fn read_loop(
+        mut reader: ReadHalf<BoxedStream>,
+        mut written_receiver: mpsc::UnboundedReceiver<Written>,
+        pushes: broadcast::Sender<RespDataTypeValue>,
+        mut decoder: RespDecoder,
+    ) {
+        let mut data = BytesMut::new();
+        let mut waiting: VecDeque<Pending> = VecDeque::new();
+        let mut subscriptions = Subscriptions::default();
+        let error = 'read: loop {
+            // A batch is handed over before it is written, so the bytes read so far only hold
+            // replies of the batches received here.
+            while let Ok(written) = written_receiver.try_recv() {
+                if let Some(error) = Self::queue(&mut waiting, written) {
+                    break 'read error;
+                }
+            }
+            loop {
+                match decoder.decode(&mut data) {
+                    Ok(Some(value)) => {
+                        if let Some(value) = Self::receive(&mut waiting, &mut subscriptions, value)
+                        {
+                            // Nobody listening is not an error, the message is dropped.
+                            let _ = pushes.send(value);
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        Self::fail_all(&mut waiting, &mut written_receiver, err);
+                        return;
+                    }
+                }
+            }
+            data.reserve(READ_BUFFER_SIZE);
+            tokio::select! {
+                written = written_receiver.recv() => match written {
+                    Some(written) => {
+                        if let Some(error) = Self::queue(&mut waiting, written) {
+                            break error;
+                        }
+                    }
+                    None => return,
+                },
+                read = reader.read_buf(&mut data) => match read {
+                    Ok(0) => break Error::ConnectionClosed,
+                    Ok(_) => {}
+                    Err(err) => break Error::Io(err),
+                },
+            }
+        };
+        Self::fail_all(&mut waiting, &mut written_receiver, error);
+    }
+
+    /// Adds a batch to the waiting ones.
+    /// Returns the error to close the connection with when the writer failed, the batches it
+    /// failed to write are answered with the error of the write.
+    fn queue(waiting: &mut VecDeque<Pending>, written: Written) -> Option<Error> {
+        match written {
+            Written::Batch(pending) if pending.expected.is_empty() => {
+                let _ = pending.reply.send(Ok(pending.results));
+                None
+            }
+            Written::Batch(pending) => {
+                waiting.push_back(pending);
+                None
+            }
+            Written::Failed(err, count) => {
+                for pending in waiting.drain(waiting.len().saturating_sub(count)..) {
+                    let _ = pending.reply.send(Err(err.duplicate()));
+                }
+                Some(Error::ConnectionClosed)
+            }
+        }
+    }
+
+    /// Hands a received value to the oldest waiting batch and answers the batch once complete.
+    /// Returns the value when it is a push message for the subscribers instead, including the
+    /// subscription confirmations no waiting command expects.
+    fn receive(
+        waiting: &mut VecDeque<Pending>,
+        subscriptions: &mut Subscriptions,
+        value: RespDataTypeValue,
+    ) -> Option<RespDataTypeValue> {
+        let confirmation = value.subscription_confirmation().map(|(kind, channel)| {
+            (
+                kind.to_ascii_lowercase(),
+                subscriptions.record(kind, channel),
+            )
+        });
+        if confirmation.is_none() && value.without_attributes().is_push() {
+            return Some(value);
+        }
+        let Some(pending) = waiting.front_mut() else {
+            // Every reply has a waiting batch, a confirmation without one was sent by the server.
+            return confirmation.map(|_| value);
+        };
+        let answered = match (pending.expected.front_mut(), &confirmation) {
+            (Some(ExpectedReplies::Confirmations(kind, count)), Some((received, _)))
+                if kind.as_bytes() == received =>
+            {
+                *count = count.saturating_sub(1);
+                *count == 0
+            }
+            (Some(ExpectedReplies::AllConfirmations(kind)), Some((received, left)))
+                if kind.as_bytes() == received =>
+            {
+                *left == 0
+            }
+            (_, Some(_)) => return Some(value),
+            // Any other reply answers the command, e.g. the error of a rejected `SUBSCRIBE`.
+            (_, None) => true,
+        };
+        pending.results.push(value);
+        if answered {
+            pending.expected.pop_front();
+        }
+        if pending.expected.is_empty()
+            && let Some(pending) = waiting.pop_front()
+        {
+            let _ = pending.reply.send(Ok(pending.results));
+        }
+        None
+    }
+
+    /// Answers the oldest waiting batch with the error and every other batch, including the ones
+    /// queued later, with `CONNECTION_CLOSED`.
+    fn fail_all(
+        waiting: &mut VecDeque<Pending>,
+        written_receiver: &mut mpsc::UnboundedReceiver<Written>,
+        error: Error,
+    ) {
+        let mut error = Some(error);
+        written_receiver.close();
+        let queued = std::iter::from_fn(|| written_receiver.try_recv().ok())
+            .filter_map(|written| match written {
+                Written::Batch(pending) => Some(pending),
+                Written::Failed(..) => None,
+            })
+            .collect::<Vec<_>>();
+        let pending = waiting.drain(..).chain(queued);
+        for pending in pending {
+            let result = error.take().unwrap_or(Error::ConnectionClosed);
+            let _ = pending.reply.send(Err(result));
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_multiplexer {
+    use super::*;
+    use crate::transport::connection::{ConnectionBuilder, ConnectionConfig};
+    use crate::types::command_kind::CommandKind;
+    use serde_json::Value;
+
+    async fn connect() -> Multiplexer {
+        let stream = ConnectionBuilder::new(&ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            ..Default::default()
+        })
+        .open()
+        .await;
+        assert!(stream.is_ok(), "{:#?}", stream.err());
+        let (stream, _) = stream.unwrap();
+        Multiplexer::new(stream)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests() {
+        let multiplexer = connect().await;
+        let mut handles = Vec::new();
+        for i in 0..100 {
+            let multiplexer = multiplexer.clone();
+            handles.push(tokio::spawn(async move {
+                let key = format!("mymultiplexer:{i}");
+                let set_command =
+                    CommandKind::Set(key.clone(), Value::String(format!("value{i}"))).build()?;
+                multiplexer.send(set_command).await?;
+                let get_command = CommandKind::Get(key).build()?;
+                multiplexer.send(get_command).await
+            }));
+        }
+        for (i, handle) in handles.into_iter().enumerate() {
+            let result = handle.await.unwrap();
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(
+                RespDataTypeValue::String(format!("value{i}")),
+                result.unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_many() {
+        let multiplexer = connect().await;
+        let mut commands = CommandKind::Ping.build().unwrap();
+        commands.extend(
+            CommandKind::Get("mymultiplexernotexists".to_string())
+                .build()
+                .unwrap(),
+        );
+        let result = multiplexer.send_many(commands, 2).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            vec![
+                RespDataTypeValue::String("PONG".to_string()),
+                RespDataTypeValue::Null
+            ],
+            result.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pushes() {
+        let multiplexer = connect().await;
+        let mut pushes = multiplexer.subscribe();
+        // The server answers PUSHME with a push message followed by +OK.
+        let result = multiplexer.send(b"*1\r\n$6\r\nPUSHME\r\n".to_vec()).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(RespDataTypeValue::String("OK".to_string()), result.unwrap());
+        let push = pushes.recv().await;
+        assert!(push.is_ok(), "{:#?}", push.err());
+        assert_eq!(
+            RespDataTypeValue::Push(vec![
+                RespDataTypeValue::String("message".to_string()),
+                RespDataTypeValue::String("hello".to_string()),
+            ]),
+            push.unwrap()
+        );
+        let result = multiplexer.send(CommandKind::Ping.build().unwrap()).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::String("PONG".to_string()),
+            result.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscription_confirmation() {
+        let (stream, mut server) = tokio::io::duplex(1024);
+        let multiplexer = Multiplexer::new(stream);
+        let mut pushes = multiplexer.subscribe();
+        // The server confirms each channel with a push. Before the PONG it sends a confirmation on
+        // its own, as when a shard channel moves, and a message.
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 1024];
+            while let Ok(read) = server.read(&mut buffer).await {
+                let reply: &[u8] = match &buffer[..read] {
+                    [] => break,
+                    command if command.windows(11).any(|w| w == b"UNSUBSCRIBE") => {
+                        b">3\r\n$11\r\nunsubscribe\r\n$4\r\nnews\r\n:1\r\n>3\r\n$11\r\nunsubscribe\r\n$6\r\nsports\r\n:0\r\n"
+                    }
+                    command if command.windows(9).any(|w| w == b"SUBSCRIBE") => {
+                        b">3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n>3\r\n$9\r\nsubscribe\r\n$6\r\nsports\r\n:2\r\n"
+                    }
+                    _ => b">3\r\n$12\r\nsunsubscribe\r\n$5\r\nshard\r\n:0\r\n>3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n+PONG\r\n",
+                };
+                if server.write_all(reply).await.is_err() {
+                    break;
+                }
+            }
+        });
+        let command = CommandKind::Custom(
+            "SUBSCRIBE".to_string(),
+            vec![b"news".to_vec(), b"sports".to_vec()],
+        );
+        let result = multiplexer
+            .send_batch(
+                command.build().unwrap(),
+                vec![command.expected_replies()],
+                Timeouts::default(),
+            )
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = result.unwrap();
+        assert_eq!(2, result.len());
+        assert!(
+            result
+                .iter()
+                .all(|value| value.is_subscription_confirmation())
+        );
+        let result = multiplexer.send(CommandKind::Ping.build().unwrap()).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::String("PONG".to_string()),
+            result.unwrap()
+        );
+        for kind in ["sunsubscribe", "message"] {
+            let push = pushes.recv().await;
+            assert!(push.is_ok(), "{:#?}", push.err());
+            match push.unwrap() {
+                RespDataTypeValue::Push(values) => {
+                    assert_eq!(RespDataTypeValue::String(kind.to_string()), values[0])
+                }
+                value => panic!("{value:?}"),
+            }
+        }
+        // A bare UNSUBSCRIBE is answered once per channel subscribed so far.
+        let command = CommandKind::Custom("UNSUBSCRIBE".to_string(), vec![]);
+        let result = multiplexer
+            .send_batch(
+                command.build().unwrap(),
+                vec![command.expected_replies()],
+                Timeouts::default(),
+            )
+            .await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            Some(&RespDataTypeValue::Push(vec![
+                RespDataTypeValue::String("unsubscribe".to_string()),
+                RespDataTypeValue::String("sports".to_string()),
+                RespDataTypeValue::Integer(0),
+            ])),
+            result.unwrap().last()
+        );
+        let result = multiplexer.send(CommandKind::Ping.build().unwrap()).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::String("PONG".to_string()),
+            result.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_closed() {
+        let multiplexer = connect().await;
+        // The server closes the connection after QUIT, later commands fail instead of hanging.
+        let result = multiplexer.send(b"*1\r\n$4\r\nQUIT\r\n".to_vec()).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let result = multiplexer.send(CommandKind::Ping.build().unwrap()).await;
+        assert!(result.is_err());
+        // The writer stops with the reader, so the multiplexer reports itself closed.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(multiplexer.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_any_stream() {
+        // Any async stream can carry the commands, here an in-memory pipe.
+        let (stream, mut server) = tokio::io::duplex(1024);
+        let multiplexer = Multiplexer::new(stream);
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 1024];
+            while let Ok(read) = server.read(&mut buffer).await {
+                if read == 0 || server.write_all(b"+PONG\r\n").await.is_err() {
+                    break;
+                }
+            }
+        });
+        let result = multiplexer.send(CommandKind::Ping.build().unwrap()).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::String("PONG".to_string()),
+            result.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_large_array() {
+        // About 1.7 MB of small elements, received in reads of at most 4 KB.
+        let (stream, mut server) = tokio::io::duplex(4096);
+        let multiplexer = Multiplexer::new(stream);
+        let length = 200_000;
+        let mut reply = format!("*{length}\r\n").into_bytes();
+        for i in 0..length {
+            reply.extend_from_slice(format!(":{i}\r\n").as_bytes());
+        }
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 1024];
+            while let Ok(read) = server.read(&mut buffer).await {
+                if read == 0 || server.write_all(&reply).await.is_err() {
+                    break;
+                }
+            }
+        });
+        let command = CommandKind::Keys("*".to_string()).build().unwrap();
+        let result = multiplexer.send(command).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        let expected = (0..length).map(RespDataTypeValue::Integer).collect();
+        assert_eq!(RespDataTypeValue::Array(expected), result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_response_timeout() {
+        let (stream, mut server) = tokio::io::duplex(1024);
+        let multiplexer = Multiplexer::new(stream);
+        // The server answers every PING after a delay.
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 1024];
+            while let Ok(read) = server.read(&mut buffer).await {
+                if read == 0 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                let count = buffer[..read].windows(4).filter(|w| w == b"PING").count();
+                if server.write_all(&b"+PONG\r\n".repeat(count)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        let timeouts = Timeouts {
+            response: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let command = CommandKind::Ping.build().unwrap();
+        let result = multiplexer
+            .send_with_timeouts(command.clone(), timeouts)
+            .await;
+        assert!(
+            matches!(&result, Err(Error::Timeout(TimeoutKind::Response))),
+            "{result:#?}"
+        );
+        // The late reply is discarded and the connection is still usable.
+        let result = multiplexer.send(command).await;
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            RespDataTypeValue::String("PONG".to_string()),
+            result.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_timeout() {
+        // The server never reads, so the write blocks once the small buffer is full.
+        let (stream, _server) = tokio::io::duplex(16);
+        let multiplexer = Multiplexer::new(stream);
+        let timeouts = Timeouts {
+            write: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let command = CommandKind::Set("mykey".to_string(), Value::String("x".repeat(1024)))
+            .build()
+            .unwrap();
+        let result = multiplexer.send_with_timeouts(command, timeouts).await;
+        assert!(
+            matches!(&result, Err(Error::Timeout(TimeoutKind::Write))),
+            "{result:#?}"
+        );
+        let result = multiplexer.send(CommandKind::Ping.build().unwrap()).await;
+        assert!(
+            matches!(result, Err(Error::ConnectionClosed)),
+            "{result:#?}"
+        );
+    }
+}
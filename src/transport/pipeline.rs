@@ -0,0 +1,82 @@
+use crate::types::ExpectedReplies;
+use crate::types::command_kind::CommandKind;
+
+/// Collects commands that are written to the server in a single batch.
+/// The replies are returned in the same order the commands were added.
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+    commands: Vec<CommandKind>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Appends a command to the batch.
+    pub fn add(&mut self, command: CommandKind) -> &mut Self {
+        self.commands.push(command);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// The replies the server sends for each command, see `CommandKind::expected_replies`.
+    pub fn expected_replies(&self) -> Vec<ExpectedReplies> {
+        self.commands
+            .iter()
+            .map(CommandKind::expected_replies)
+            .collect()
+    }
+
+    /// Whether every command of the batch can be retried, see `CommandKind::is_idempotent`.
+    pub fn is_idempotent(&self) -> bool {
+        self.commands.iter().all(CommandKind::is_idempotent)
+    }
+
+    /// Encodes every command of the batch back to back.
+    pub fn build(&self) -> crate::Result<Vec<u8>> {
+        let mut data: Vec<u8> = Vec::new();
+        for command in self.commands.iter() {
+            data.extend(command.build()?);
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+pub mod test_pipeline {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let mut pipeline = Pipeline::new();
+        pipeline
+            .add(CommandKind::Ping)
+            .add(CommandKind::Get("mykey".to_string()));
+        assert_eq!(2, pipeline.len());
+        let result = pipeline.build();
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(
+            b"*1\r\n$4\r\nPING\r\n*2\r\n$3\r\nGET\r\n$5\r\nmykey\r\n".to_vec(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_error() {
+        let mut pipeline = Pipeline::new();
+        pipeline
+            .add(CommandKind::Ping)
+            .add(CommandKind::Get("".to_string()));
+        assert!(pipeline.build().is_err());
+    }
+}
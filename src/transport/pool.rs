@@ -0,0 +1,415 @@
+use crate::transport::client::Client;
+use crate::transport::connection::ConnectionConfig;
+use crate::transport::timeouts::{Timeouts, within};
+use crate::types::RespDataTypeValue;
+use crate::types::command_kind::CommandKind;
+use crate::{Error, TimeoutKind};
+use std::collections::VecDeque;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// The number of connections opened when the pool is created and kept open while idle.
+    pub min_size: usize,
+    /// The maximum number of connections the pool opens.
+    pub max_size: usize,
+    /// Idle connections older than this are closed on the next checkout, down to `min_size`.
+    pub idle_timeout: Option<Duration>,
+    /// How long a checkout may take before failing with `POOL_CHECKOUT_TIMEOUT`, including the
+    /// wait for a free connection, the health check and opening a new connection.
+    pub checkout_timeout: Option<Duration>,
+    /// Sends a `PING` before handing out an idle connection and discards it if the check fails.
+    pub health_check: bool,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 0,
+            max_size: 10,
+            idle_timeout: Some(Duration::from_secs(300)),
+            checkout_timeout: Some(Duration::from_secs(30)),
+            health_check: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct IdleClient {
+    client: Client,
+    since: Instant,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+    config: ConnectionConfig,
+    pool_config: PoolConfig,
+    idle: Mutex<VecDeque<IdleClient>>,
+    semaphore: Arc<Semaphore>,
+    size: AtomicUsize,
+}
+
+/// A pool of clients that caps the number of connections opened against the server.
+/// Cloning a `Pool` is cheap and every clone shares the same connections.
+#[derive(Debug, Clone)]
+pub struct Pool {
+    inner: Arc<PoolInner>,
+}
+
+impl Pool {
+    /// Creates the pool and opens `min_size` connections.
+    pub async fn new(config: ConnectionConfig, pool_config: PoolConfig) -> crate::Result<Self> {
+        if pool_config.max_size == 0 {
+            return Err(Error::InvalidConfig("POOL_MAX_SIZE_REQUIRED".to_string()));
+        }
+        if pool_config.min_size > pool_config.max_size {
+            return Err(Error::InvalidConfig(
+                "POOL_MIN_SIZE_EXCEEDS_MAX_SIZE".to_string(),
+            ));
+        }
+        let inner = Arc::new(PoolInner {
+            semaphore: Arc::new(Semaphore::new(pool_config.max_size)),
+            idle: Mutex::new(VecDeque::new()),
+            size: AtomicUsize::new(0),
+            config,
+            pool_config,
+        });
+        for _ in 0..inner.pool_config.min_size {
+            let client = Client::new(inner.config.clone()).await?;
+            inner.size.fetch_add(1, Ordering::SeqCst);
+            inner.release(client);
+        }
+        Ok(Self { inner })
+    }
+
+    /// Checks out a client, reusing an idle connection when one is available.
+    /// The client goes back to the pool when the returned guard is dropped.
+    /// `checkout_timeout` bounds the whole checkout: waiting for a free connection, the health
+    /// checks and opening a new connection.
+    pub async fn get(&self) -> crate::Result<PooledClient> {
+        let deadline = self
+            .inner
+            .pool_config
+            .checkout_timeout
+            .map(|duration| Instant::now() + duration);
+        let semaphore = self.inner.semaphore.clone();
+        let permit = within(remaining(deadline), TimeoutKind::PoolCheckout, async {
+            semaphore
+                .acquire_owned()
+                .await
+                .map_err(|_| Error::ConnectionClosed)
+        })
+        .await?;
+        while let Some(idle) = self.inner.take_idle() {
+            if self.inner.is_expired(&idle)
+                || !self
+                    .inner
+                    .is_healthy(&idle.client, remaining(deadline))
+                    .await
+            {
+                self.inner.size.fetch_sub(1, Ordering::SeqCst);
+                continue;
+            }
+            return Ok(PooledClient::new(idle.client, self.inner.clone(), permit));
+        }
+        let client = within(
+            remaining(deadline),
+            TimeoutKind::PoolCheckout,
+            Client::new(self.inner.config.clone()),
+        )
+        .await?;
+        self.inner.size.fetch_add(1, Ordering::SeqCst);
+        Ok(PooledClient::new(client, self.inner.clone(), permit))
+    }
+
+    /// The number of open connections, idle or checked out.
+    pub fn size(&self) -> usize {
+        self.inner.size.load(Ordering::SeqCst)
+    }
+
+    /// The number of connections waiting in the pool.
+    pub fn idle_size(&self) -> usize {
+        self.inner
+            .idle
+            .lock()
+            .map(|idle| idle.len())
+            .unwrap_or_default()
+    }
+}
+
+impl PoolInner {
+    fn take_idle(&self) -> Option<IdleClient> {
+        self.idle.lock().ok()?.pop_front()
+    }
+
+    fn release(&self, client: Client) {
+        if client.is_closed() {
+            self.size.fetch_sub(1, Ordering::SeqCst);
+            return;
+        }
+        if let Ok(mut idle) = self.idle.lock() {
+            idle.push_back(IdleClient {
+                client,
+                since: Instant::now(),
+            });
+        }
+    }
+
+    fn is_expired(&self, idle: &IdleClient) -> bool {
+        match self.pool_config.idle_timeout {
+            Some(timeout) => {
+                idle.since.elapsed() > timeout
+                    && self.size.load(Ordering::SeqCst) > self.pool_config.min_size
+            }
+            None => false,
+        }
+    }
+
+    /// Sends a `PING` on the connection of the client, without reopening it when it is broken.
+    /// Parameters:
+    /// - client - The idle client.
+    /// - limit - The time left for the checkout, the check fails when the reply takes longer.
+    async fn is_healthy(&self, client: &Client, limit: Option<Duration>) -> bool {
+        if !self.pool_config.health_check || client.is_closed() {
+            return !client.is_closed();
+        }
+        let Ok(command) = CommandKind::Ping.build() else {
+            return false;
+        };
+        let timeouts = client.config.timeouts();
+        let timeouts = Timeouts {
+            write: shortest(timeouts.write, limit),
+            response: shortest(timeouts.response, limit),
+        };
+        matches!(
+            client.multiplexer().send_with_timeouts(command, timeouts).await,
+            Ok(RespDataTypeValue::String(value)) if value == "PONG"
+        )
+    }
+}
+
+/// The time left before the deadline, `None` when there is no deadline.
+fn remaining(deadline: Option<Instant>) -> Option<Duration> {
+    deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// The shortest of two time limits, `None` meaning no limit.
+fn shortest(first: Option<Duration>, second: Option<Duration>) -> Option<Duration> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(first.min(second)),
+        (first, second) => first.or(second),
+    }
+}
+
+/// A client checked out of a `Pool`. It dereferences to `Client` and returns to the pool on drop.
+#[derive(Debug)]
+pub struct PooledClient {
+    client: Option<Client>,
+    pool: Arc<PoolInner>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledClient {
+    fn new(client: Client, pool: Arc<PoolInner>, permit: OwnedSemaphorePermit) -> Self {
+        Self {
+            client: Some(client),
+            pool,
+            _permit: permit,
+        }
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Self::Target {
+        self.client.as_ref().expect("POOLED_CLIENT_RELEASED")
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            self.pool.release(client);
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_pool {
+    use super::*;
+
+    fn config() -> ConnectionConfig {
+        ConnectionConfig {
+            address: "127.0.0.1:6379".to_string(),
+            username: Some("myapp".to_string()),
+            password: Some("password123".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config() {
+        let result = Pool::new(
+            config(),
+            PoolConfig {
+                max_size: 0,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        let result = Pool::new(
+            config(),
+            PoolConfig {
+                min_size: 2,
+                max_size: 1,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reuse() {
+        let pool = Pool::new(
+            config(),
+            PoolConfig {
+                min_size: 1,
+                max_size: 2,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(pool.is_ok(), "{:#?}", pool.err());
+        let pool = pool.unwrap();
+        assert_eq!(1, pool.size());
+        assert_eq!(1, pool.idle_size());
+        {
+            let client = pool.get().await;
+            assert!(client.is_ok(), "{:#?}", client.err());
+            let client = client.unwrap();
+            let result = client.send(CommandKind::Ping).await;
+            assert!(result.is_ok(), "{:#?}", result.err());
+            assert_eq!(0, pool.idle_size());
+        }
+        assert_eq!(1, pool.size());
+        assert_eq!(1, pool.idle_size());
+    }
+
+    #[tokio::test]
+    async fn test_checkout_timeout() {
+        let pool = Pool::new(
+            config(),
+            PoolConfig {
+                max_size: 1,
+                checkout_timeout: Some(Duration::from_millis(100)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let first = pool.get().await;
+        assert!(first.is_ok(), "{:#?}", first.err());
+        let second = pool.get().await;
+        assert!(second.is_err());
+        drop(first);
+        let third = pool.get().await;
+        assert!(third.is_ok(), "{:#?}", third.err());
+        assert_eq!(1, pool.size());
+    }
+
+    #[tokio::test]
+    async fn test_checkout_timeout_unresponsive() {
+        use std::sync::atomic::AtomicBool;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // The server answers until it is told to stop, then reads without ever replying.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let answering = Arc::new(AtomicBool::new(true));
+        let server = answering.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let answering = server.clone();
+                tokio::spawn(async move {
+                    let mut buffer = [0u8; 1024];
+                    while let Ok(read) = stream.read(&mut buffer).await {
+                        if read == 0 {
+                            break;
+                        }
+                        if !answering.load(Ordering::SeqCst) {
+                            continue;
+                        }
+                        let reply: &[u8] = match buffer[..read].windows(4).any(|w| w == b"PING") {
+                            true => b"+PONG\r\n",
+                            false => b"+OK\r\n",
+                        };
+                        if stream.write_all(reply).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        let pool = Pool::new(
+            ConnectionConfig {
+                address,
+                ..config()
+            },
+            PoolConfig {
+                min_size: 1,
+                max_size: 1,
+                checkout_timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(pool.is_ok(), "{:#?}", pool.err());
+        let pool = pool.unwrap();
+        answering.store(false, Ordering::SeqCst);
+        // Neither the health check of the idle connection nor the handshake of a new one can
+        // hold the checkout past its timeout.
+        let start = Instant::now();
+        let result = pool.get().await;
+        assert!(
+            matches!(&result, Err(Error::Timeout(TimeoutKind::PoolCheckout))),
+            "{result:#?}"
+        );
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(0, pool.size());
+    }
+
+    #[tokio::test]
+    async fn test_workers() {
+        let pool = Pool::new(
+            config(),
+            PoolConfig {
+                max_size: 4,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let mut handles = Vec::new();
+        for i in 0..20 {
+            let pool = pool.clone();
+            handles.push(tokio::spawn(async move {
+                let client = pool.get().await?;
+                client
+                    .send(CommandKind::Increment(format!("mypool:{i}")))
+                    .await
+            }));
+        }
+        for handle in handles {
+            let result = handle.await.unwrap();
+            assert!(result.is_ok(), "{:#?}", result.err());
+        }
+        assert!(pool.size() <= 4);
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How a client reopens a broken connection.
+/// The first attempt is immediate, then the delay grows exponentially up to `max_delay`, and part
+/// of it is randomized so clients that lost the same server don't reconnect all at once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectConfig {
+    /// The number of connection attempts before giving up, `None` tries forever.
+    pub max_attempts: Option<u32>,
+    /// The delay after the first failed attempt.
+    pub initial_delay: Duration,
+    /// The longest delay between two attempts.
+    pub max_delay: Duration,
+    /// The factor applied to the delay after each failed attempt, at least 1.
+    pub multiplier: f64,
+    /// The fraction of the delay that is randomized, from 0 (fixed) to 1 (anywhere up to the delay).
+    pub jitter: f64,
+    /// Sends a command again on the new connection when it failed with the broken one and
+    /// repeating it is safe, see `CommandKind::is_idempotent`.
+    pub retry_idempotent: bool,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: Some(10),
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.5,
+            retry_idempotent: true,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// Returns the delay before the next attempt.
+    /// Parameters:
+    /// - failures - The number of attempts that failed so far, at least 1.
+    pub fn delay(&self, failures: u32) -> Duration {
+        let exponent = failures.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_delay.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        let delay = delay.min(self.max_delay.as_secs_f64());
+        let jitter = self.jitter.clamp(0.0, 1.0) * random();
+        Duration::try_from_secs_f64(delay * (1.0 - jitter)).unwrap_or(self.max_delay)
+    }
+}
+
+/// Returns a number in `[0, 1)` for spreading the delays, not for anything security related.
+/// Each thread seeds the `RandomState` keys from the OS once and every `new()` increments them, so
+/// hashing the same input gives a different number on each call and in each process.
+fn random() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+pub mod test_reconnect {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let config = ReconnectConfig {
+            jitter: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(Duration::from_millis(100), config.delay(1));
+        assert_eq!(Duration::from_millis(200), config.delay(2));
+        assert_eq!(Duration::from_millis(800), config.delay(4));
+        assert_eq!(Duration::from_secs(10), config.delay(20));
+        assert_eq!(Duration::from_secs(10), config.delay(u32::MAX));
+    }
+
+    #[test]
+    fn test_jitter() {
+        let config = ReconnectConfig::default();
+        let delays = (0..100).map(|_| config.delay(3)).collect::<Vec<_>>();
+        for delay in delays.iter() {
+            assert!(*delay > Duration::from_millis(200), "{delay:?}");
+            assert!(*delay <= Duration::from_millis(400), "{delay:?}");
+        }
+        assert!(delays.iter().any(|delay| *delay != delays[0]));
+    }
+}
@@ -0,0 +1,99 @@
+use bytes::BytesMut;
+use std::fmt::Debug;
+use std::io::{self, IoSlice};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A connected byte stream the commands are written to and the replies read from.
+/// It is implemented for every async stream, e.g. `TcpStream`, `UnixStream` or a TLS stream,
+/// so `Multiplexer` and `Execute` work the same over any of them.
+pub trait Stream: AsyncRead + AsyncWrite + Unpin + Send + Sync + Debug {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + Sync + Debug> Stream for T {}
+
+/// A stream whose type is picked at runtime, as opened by `ConnectionBuilder::open`.
+pub type BoxedStream = Box<dyn Stream>;
+
+/// A stream that returns bytes already read from the inner stream before reading it again, e.g.
+/// the bytes the server sent right after the last reply of the handshake.
+#[derive(Debug)]
+pub(crate) struct PrefixedStream<S> {
+    prefix: BytesMut,
+    inner: S,
+}
+
+impl<S: Stream> PrefixedStream<S> {
+    pub(crate) fn new(prefix: BytesMut, inner: S) -> Self {
+        Self { prefix, inner }
+    }
+}
+
+impl<S: Stream> AsyncRead for PrefixedStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.prefix.is_empty() {
+            return Pin::new(&mut this.inner).poll_read(cx, buf);
+        }
+        let length = this.prefix.len().min(buf.remaining());
+        buf.put_slice(&this.prefix.split_to(length));
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: Stream> AsyncWrite for PrefixedStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+pub mod test_stream {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_prefixed() {
+        let (stream, mut server) = tokio::io::duplex(1024);
+        let mut stream = PrefixedStream::new(BytesMut::from(&b"+OK\r\n"[..]), stream);
+        server.write_all(b"+PONG\r\n").await.unwrap();
+        let mut buffer = [0u8; 3];
+        stream.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(b"+OK", &buffer);
+        let mut buffer = [0u8; 9];
+        stream.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(b"\r\n+PONG\r\n", &buffer);
+        stream.write_all(b"PING").await.unwrap();
+        let mut buffer = [0u8; 4];
+        server.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(b"PING", &buffer);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{Error, TimeoutKind};
+use std::time::Duration;
+
+/// The time limits of a call, `None` waits as long as it takes.
+/// Both fail with `Error::Timeout`, so a slow server can be told apart from a failed connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timeouts {
+    /// The maximum time to write the commands to the socket.
+    /// Exceeding it fails with `WRITE_TIMEOUT` and closes the connection, since a command may be
+    /// partially written.
+    pub write: Option<Duration>,
+    /// The maximum time from submitting the commands to receiving their replies.
+    /// Exceeding it fails with `RESPONSE_TIMEOUT`.
+    pub response: Option<Duration>,
+}
+
+/// Runs the future, failing with `Error::Timeout(kind)` when it takes longer than the duration.
+/// Parameters:
+/// - duration - The time limit, `None` waits for the future to complete.
+/// - kind - The operation that timed out, e.g. `TimeoutKind::Response`.
+pub(crate) async fn within<T>(
+    duration: Option<Duration>,
+    kind: TimeoutKind,
+    future: impl Future<Output = crate::Result<T>>,
+) -> crate::Result<T> {
+    match duration {
+        Some(duration) => match tokio::time::timeout(duration, future).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::Timeout(kind)),
+        },
+        None => future.await,
+    }
+}
@@ -0,0 +1,391 @@
+use crate::Error;
+use crate::transport::stream::Stream;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{self, CryptoProvider};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+
+/// The TLS settings of a connection, the connection is encrypted when they are set.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// A PEM file with the CA certificates trusted to sign the server certificate.
+    /// The Mozilla root certificates are trusted when it is not set.
+    pub ca_file: Option<PathBuf>,
+    /// A PEM file with the client certificate chain, sent when the server asks for one (mTLS).
+    pub client_cert_file: Option<PathBuf>,
+    /// A PEM file with the private key of the client certificate.
+    pub client_key_file: Option<PathBuf>,
+    /// The name sent in SNI and matched against the server certificate.
+    /// Defaults to the host of the connection address.
+    pub server_name: Option<String>,
+    /// Accepts any server certificate. The connection is encrypted but the server is not
+    /// authenticated, so it must only be used in development.
+    pub insecure_skip_verify: bool,
+}
+
+impl TlsConfig {
+    /// Builds the `rustls` client configuration from the settings.
+    /// The certificate and key files are read on every call.
+    pub fn client_config(&self) -> crate::Result<ClientConfig> {
+        let provider = Arc::new(crypto::ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|err| Error::InvalidConfig(format!("TLS_PROTOCOL_INVALID: {err}")))?;
+        let builder = if self.insecure_skip_verify {
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(SkipVerify { provider }))
+        } else {
+            builder.with_root_certificates(self.root_certificates()?)
+        };
+        let config = match (&self.client_cert_file, &self.client_key_file) {
+            (Some(cert_file), Some(key_file)) => {
+                let certificates = read_certificates(cert_file, "TLS_CLIENT_CERT_INVALID")?;
+                let key = PrivateKeyDer::from_pem_file(key_file).map_err(|err| {
+                    Error::InvalidConfig(format!("TLS_CLIENT_KEY_INVALID: {err}"))
+                })?;
+                builder
+                    .with_client_auth_cert(certificates, key)
+                    .map_err(|err| Error::InvalidConfig(format!("TLS_CLIENT_KEY_INVALID: {err}")))?
+            }
+            (None, None) => builder.with_no_client_auth(),
+            _ => {
+                return Err(Error::InvalidConfig(
+                    "TLS_CLIENT_CERT_AND_KEY_REQUIRED".to_string(),
+                ));
+            }
+        };
+        Ok(config)
+    }
+
+    /// Performs the TLS handshake over a connected stream.
+    /// Parameters:
+    /// - stream - The connected stream.
+    /// - address - The address the stream is connected to, its host is the default server name.
+    pub async fn connect<S: Stream>(
+        &self,
+        stream: S,
+        address: &str,
+    ) -> crate::Result<TlsStream<S>> {
+        let name = match &self.server_name {
+            Some(name) => name.clone(),
+            None => host(address).to_string(),
+        };
+        let server_name = ServerName::try_from(name)
+            .map_err(|err| Error::InvalidConfig(format!("TLS_SERVER_NAME_INVALID: {err}")))?;
+        let connector = TlsConnector::from(Arc::new(self.client_config()?));
+        Ok(connector.connect(server_name, stream).await?)
+    }
+
+    fn root_certificates(&self) -> crate::Result<RootCertStore> {
+        let mut roots = RootCertStore::empty();
+        match &self.ca_file {
+            Some(ca_file) => {
+                for certificate in read_certificates(ca_file, "TLS_CA_FILE_INVALID")? {
+                    roots.add(certificate).map_err(|err| {
+                        Error::InvalidConfig(format!("TLS_CA_FILE_INVALID: {err}"))
+                    })?;
+                }
+            }
+            None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+        }
+        Ok(roots)
+    }
+}
+
+/// Reads every certificate of a PEM file, the file must hold at least one.
+fn read_certificates(path: &PathBuf, code: &str) -> crate::Result<Vec<CertificateDer<'static>>> {
+    let certificates = CertificateDer::pem_file_iter(path)
+        .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+        .map_err(|err| Error::InvalidConfig(format!("{code}: {err}")))?;
+    if certificates.is_empty() {
+        return Err(Error::InvalidConfig(format!(
+            "{code}: no certificate found"
+        )));
+    }
+    Ok(certificates)
+}
+
+/// Returns the host of a `host:port` address, without the brackets of an IPv6 literal.
+fn host(address: &str) -> &str {
+    let host = match address.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => host,
+        _ => address,
+    };
+    host.trim_start_matches('[').trim_end_matches(']')
+}
+
+/// Accepts any server certificate, the handshake signatures are still checked so the
+/// connection is bound to the certificate's key.
+#[derive(Debug)]
+struct SkipVerify {
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for SkipVerify {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+pub mod test_tls {
+    use super::*;
+    use crate::transport::client::Client;
+    use crate::transport::connection::ConnectionConfig;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+    use tokio_rustls::rustls::ServerConfig;
+    use tokio_rustls::rustls::server::WebPkiClientVerifier;
+
+    fn certificate(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/certs")
+            .join(name)
+    }
+
+    /// Starts a TLS server signed by the test CA, answering `+PONG` to every read.
+    /// Returns the address it listens on.
+    async fn serve(require_client_cert: bool) -> String {
+        let provider = Arc::new(crypto::ring::default_provider());
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .unwrap();
+        let builder = if require_client_cert {
+            let mut roots = RootCertStore::empty();
+            for certificate in read_certificates(&certificate("ca.pem"), "").unwrap() {
+                roots.add(certificate).unwrap();
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                .build()
+                .unwrap();
+            builder.with_client_cert_verifier(verifier)
+        } else {
+            builder.with_no_client_auth()
+        };
+        let config = builder
+            .with_single_cert(
+                read_certificates(&certificate("server.pem"), "").unwrap(),
+                PrivateKeyDer::from_pem_file(certificate("server.key")).unwrap(),
+            )
+            .unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    let Ok(mut stream) = acceptor.accept(stream).await else {
+                        return;
+                    };
+                    let mut buffer = [0u8; 1024];
+                    while let Ok(read) = stream.read(&mut buffer).await {
+                        if read == 0 || stream.write_all(b"+PONG\r\n").await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        address
+    }
+
+    async fn ping(address: String, tls: TlsConfig) -> crate::Result<String> {
+        let client = Client::new(ConnectionConfig {
+            address,
+            tls: Some(tls),
+            ..Default::default()
+        })
+        .await?;
+        client.ping().await
+    }
+
+    #[tokio::test]
+    async fn test_connect() {
+        struct TestCase {
+            pub name: &'static str,
+            pub require_client_cert: bool,
+            pub tls: TlsConfig,
+            pub expected: bool,
+        }
+        let test_cases = vec![
+            TestCase {
+                name: "ca_file",
+                require_client_cert: false,
+                tls: TlsConfig {
+                    ca_file: Some(certificate("ca.pem")),
+                    ..Default::default()
+                },
+                expected: true,
+            },
+            TestCase {
+                name: "untrusted",
+                require_client_cert: false,
+                tls: TlsConfig::default(),
+                expected: false,
+            },
+            TestCase {
+                name: "insecure_skip_verify",
+                require_client_cert: false,
+                tls: TlsConfig {
+                    insecure_skip_verify: true,
+                    ..Default::default()
+                },
+                expected: true,
+            },
+            TestCase {
+                name: "server_name",
+                require_client_cert: false,
+                tls: TlsConfig {
+                    ca_file: Some(certificate("ca.pem")),
+                    server_name: Some("localhost".to_string()),
+                    ..Default::default()
+                },
+                expected: true,
+            },
+            TestCase {
+                name: "server_name_mismatch",
+                require_client_cert: false,
+                tls: TlsConfig {
+                    ca_file: Some(certificate("ca.pem")),
+                    server_name: Some("valkey.example.com".to_string()),
+                    ..Default::default()
+                },
+                expected: false,
+            },
+            TestCase {
+                name: "client_cert",
+                require_client_cert: true,
+                tls: TlsConfig {
+                    ca_file: Some(certificate("ca.pem")),
+                    client_cert_file: Some(certificate("client.pem")),
+                    client_key_file: Some(certificate("client.key")),
+                    ..Default::default()
+                },
+                expected: true,
+            },
+            TestCase {
+                name: "client_cert_missing",
+                require_client_cert: true,
+                tls: TlsConfig {
+                    ca_file: Some(certificate("ca.pem")),
+                    ..Default::default()
+                },
+                expected: false,
+            },
+        ];
+        for test_case in test_cases {
+            let address = serve(test_case.require_client_cert).await;
+            let result = ping(address, test_case.tls).await;
+            if test_case.expected {
+                assert!(result.is_ok(), "{}: {:#?}", test_case.name, result.err());
+                assert_eq!("PONG", result.unwrap());
+            } else {
+                assert!(result.is_err(), "{}", test_case.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_client_config() {
+        struct TestCase {
+            pub tls: TlsConfig,
+            pub expected: &'static str,
+        }
+        let test_cases = vec![
+            TestCase {
+                tls: TlsConfig {
+                    ca_file: Some(certificate("missing.pem")),
+                    ..Default::default()
+                },
+                expected: "TLS_CA_FILE_INVALID",
+            },
+            TestCase {
+                tls: TlsConfig {
+                    ca_file: Some(certificate("client.key")),
+                    ..Default::default()
+                },
+                expected: "TLS_CA_FILE_INVALID",
+            },
+            TestCase {
+                tls: TlsConfig {
+                    client_cert_file: Some(certificate("client.pem")),
+                    ..Default::default()
+                },
+                expected: "TLS_CLIENT_CERT_AND_KEY_REQUIRED",
+            },
+            TestCase {
+                tls: TlsConfig {
+                    client_cert_file: Some(certificate("client.pem")),
+                    client_key_file: Some(certificate("ca.pem")),
+                    ..Default::default()
+                },
+                expected: "TLS_CLIENT_KEY_INVALID",
+            },
+        ];
+        for test_case in test_cases {
+            let result = test_case.tls.client_config();
+            assert!(result.is_err());
+            let error = result.err().unwrap().to_string();
+            assert!(error.starts_with(test_case.expected), "{error}");
+        }
+    }
+
+    #[test]
+    fn test_host() {
+        assert_eq!("127.0.0.1", host("127.0.0.1:6379"));
+        assert_eq!("valkey.internal", host("valkey.internal:6380"));
+        assert_eq!("::1", host("[::1]:6379"));
+        assert_eq!("valkey.internal", host("valkey.internal"));
+    }
+}
@@ -1,16 +1,38 @@
 use crate::{
     builder::commands::{
-        Auth, AuthConfig, Decrement, DecrementBy, Exists, Expire, ExpireAt, ExpireTime, Get, Hello,
-        Increment, IncrementBy, Keys, Ping, Raw, Set, Ttl, delete::Delete,
+        Auth, AuthConfig, Custom, Decrement, DecrementBy, Exists, Expire, ExpireAt, ExpireTime,
+        Get, Hello, HelloConfig, Increment, IncrementBy, Keys, Ping, Raw, Select, Set, Ttl,
+        delete::Delete,
     },
-    types::ExpiryKind,
+    types::{ExpectedReplies, ExpiryKind},
 };
 use serde_json::Value;
+
+/// The commands a RESP3 server answers with one push per channel instead of a reply.
+pub(crate) const SUBSCRIPTION_COMMANDS: [&str; 6] = [
+    "SUBSCRIBE",
+    "UNSUBSCRIBE",
+    "PSUBSCRIBE",
+    "PUNSUBSCRIBE",
+    "SSUBSCRIBE",
+    "SUNSUBSCRIBE",
+];
+
+/// Checks if the name is one of `SUBSCRIBE`, `UNSUBSCRIBE` or their pattern and shard variants,
+/// in any case.
+pub fn is_subscription_command(name: &[u8]) -> bool {
+    SUBSCRIPTION_COMMANDS
+        .iter()
+        .any(|command| name.eq_ignore_ascii_case(command.as_bytes()))
+}
+
 #[derive(Debug, Clone)]
 pub enum CommandKind {
     Auth(AuthConfig),
     Get(String),
     Set(String, Value),
+    /// A `SET` with a binary-safe value.
+    SetBytes(String, Vec<u8>),
     Raw(String),
     Delete(Vec<String>),
     Increment(String),
@@ -20,14 +42,19 @@ pub enum CommandKind {
     Ping,
     Ttl(String),
     Keys(String),
-    Hello,
+    Hello(HelloConfig),
     Expire(String, u64, Option<ExpiryKind>),
     ExpireAt(String, u64, Option<ExpiryKind>),
     ExpireTime(String),
     Exists(Vec<String>),
+    /// Switches the connection to the numbered database.
+    Select(u32),
+    /// Any command, built from its name and binary-safe arguments.
+    Custom(String, Vec<Vec<u8>>),
 }
 impl CommandKind {
-    pub fn build(&self) -> anyhow::Result<String> {
+    /// Encodes the command into the bytes sent to the server.
+    pub fn build(&self) -> crate::Result<Vec<u8>> {
         match self {
             Self::Auth(config) => Auth::build(config),
             Self::Get(value) => Get::build(value),
@@ -37,15 +64,49 @@ impl CommandKind {
             Self::Delete(values) => Delete::build(values),
             Self::Raw(message) => Raw::build(message),
             Self::Set(key, value) => Set::build(key, value),
+            Self::SetBytes(key, value) => Set::build_bytes(key, value),
             Self::Increment(key) => Increment::build(key),
             Self::Decrement(key) => Decrement::build(key),
             Self::IncrementBy(key, value) => IncrementBy::build(key, value),
             Self::DecrementBy(key, value) => DecrementBy::build(key, value),
             Self::Keys(value) => Keys::build(value),
-            Self::Hello => Hello::build(),
+            Self::Hello(config) => Hello::build(config),
             Self::ExpireAt(key, duration, kind) => ExpireAt::build(key, duration, kind),
             Self::ExpireTime(key) => ExpireTime::build(key),
             Self::Exists(keys) => Exists::build(keys),
+            Self::Select(database) => Select::build(database),
+            Self::Custom(name, args) => Custom::build(name, args),
+        }
+    }
+
+    /// Whether sending the command twice has the same effect as sending it once, so it can be
+    /// retried when the connection broke before its reply was received.
+    /// Only the read-only commands qualify, `Raw` and `Custom` are never retried.
+    pub fn is_idempotent(&self) -> bool {
+        matches!(
+            self,
+            Self::Get(_)
+                | Self::Ping
+                | Self::Ttl(_)
+                | Self::Keys(_)
+                | Self::ExpireTime(_)
+                | Self::Exists(_)
+        )
+    }
+
+    /// The replies the server sends for the command.
+    /// A subscription command (`SUBSCRIBE`, `UNSUBSCRIBE` and their pattern and shard variants)
+    /// is answered once per channel, and a bare `UNSUBSCRIBE` once per current subscription.
+    /// The words of a `Raw` command are split on whitespace, quoted arguments aren't supported.
+    pub fn expected_replies(&self) -> ExpectedReplies {
+        match self {
+            Self::Custom(name, args) => ExpectedReplies::of(name.as_bytes(), args.len()),
+            Self::Raw(value) => {
+                let mut words = value.split_whitespace();
+                let name = words.next().unwrap_or_default();
+                ExpectedReplies::of(name.as_bytes(), words.count())
+            }
+            _ => ExpectedReplies::One,
         }
     }
 }
@@ -58,7 +119,10 @@ pub mod test_command_kind {
     fn test_get() {
         let result = CommandKind::Get("mykey".to_string()).build();
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("GET mykey\r\n", result.unwrap());
+        assert_eq!(
+            b"*2\r\n$3\r\nGET\r\n$5\r\nmykey\r\n".to_vec(),
+            result.unwrap()
+        );
     }
 
     #[test]
@@ -66,6 +130,43 @@ pub mod test_command_kind {
         let result =
             CommandKind::Set("mykey".to_string(), Value::String("myvalue".to_string())).build();
         assert!(result.is_ok(), "{:#?}", result.err());
-        assert_eq!("SET mykey myvalue\r\n", result.unwrap());
+        assert_eq!(
+            b"*3\r\n$3\r\nSET\r\n$5\r\nmykey\r\n$7\r\nmyvalue\r\n".to_vec(),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        assert!(CommandKind::Get("mykey".to_string()).is_idempotent());
+        assert!(CommandKind::Ping.is_idempotent());
+        assert!(!CommandKind::Increment("mykey".to_string()).is_idempotent());
+        assert!(!CommandKind::Custom("GET".to_string(), vec![b"mykey".to_vec()]).is_idempotent());
+    }
+
+    #[test]
+    fn test_expected_replies() {
+        let channels = vec![b"news".to_vec(), b"sports".to_vec()];
+        assert_eq!(
+            ExpectedReplies::Confirmations("subscribe".to_string(), 2),
+            CommandKind::Custom("subscribe".to_string(), channels.clone()).expected_replies()
+        );
+        assert_eq!(
+            ExpectedReplies::Confirmations("punsubscribe".to_string(), 2),
+            CommandKind::Custom("PUNSUBSCRIBE".to_string(), channels).expected_replies()
+        );
+        assert_eq!(
+            ExpectedReplies::Confirmations("subscribe".to_string(), 2),
+            CommandKind::Raw("SUBSCRIBE  news sports".to_string()).expected_replies()
+        );
+        assert_eq!(
+            ExpectedReplies::AllConfirmations("unsubscribe".to_string()),
+            CommandKind::Custom("UNSUBSCRIBE".to_string(), vec![]).expected_replies()
+        );
+        assert_eq!(
+            ExpectedReplies::One,
+            CommandKind::Custom("GET".to_string(), vec![b"mykey".to_vec()]).expected_replies()
+        );
+        assert_eq!(ExpectedReplies::One, CommandKind::Ping.expected_replies());
     }
 }
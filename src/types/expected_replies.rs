@@ -0,0 +1,54 @@
+use crate::types::command_kind::SUBSCRIPTION_COMMANDS;
+
+/// The replies the server sends for a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectedReplies {
+    /// A single reply.
+    One,
+    /// One confirmation per channel, with the lowercase command name as its kind, e.g.
+    /// `SUBSCRIBE news sports` is answered with two `subscribe` pushes.
+    Confirmations(String, usize),
+    /// One confirmation per current subscription of the kind, or a single one without any
+    /// subscription, e.g. a bare `UNSUBSCRIBE`.
+    AllConfirmations(String),
+}
+
+impl ExpectedReplies {
+    /// Returns the replies of a command.
+    /// Parameters:
+    /// - name - The command name, in any case.
+    /// - args - The number of arguments, the channels of a subscription command.
+    pub fn of(name: &[u8], args: usize) -> Self {
+        let Some(kind) = SUBSCRIPTION_COMMANDS
+            .iter()
+            .find(|command| name.eq_ignore_ascii_case(command.as_bytes()))
+        else {
+            return Self::One;
+        };
+        match args {
+            0 if kind.ends_with("UNSUBSCRIBE") => Self::AllConfirmations(kind.to_ascii_lowercase()),
+            // A subscription without channels is answered with an error.
+            0 => Self::One,
+            count => Self::Confirmations(kind.to_ascii_lowercase(), count),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_expected_replies {
+    use super::*;
+
+    #[test]
+    fn test_of() {
+        assert_eq!(ExpectedReplies::One, ExpectedReplies::of(b"GET", 1));
+        assert_eq!(
+            ExpectedReplies::Confirmations("subscribe".to_string(), 2),
+            ExpectedReplies::of(b"subscribe", 2)
+        );
+        assert_eq!(
+            ExpectedReplies::AllConfirmations("punsubscribe".to_string()),
+            ExpectedReplies::of(b"PUNSUBSCRIBE", 0)
+        );
+        assert_eq!(ExpectedReplies::One, ExpectedReplies::of(b"SSUBSCRIBE", 0));
+    }
+}
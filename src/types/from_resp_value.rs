@@ -0,0 +1,339 @@
+use crate::Error;
+use crate::types::RespDataTypeValue;
+use bytes::Bytes;
+use num_bigint::BigInt;
+use ordered_float::OrderedFloat;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Converts a reply into a Rust type, e.g. the reply to `GET` into `Option<String>`.
+/// Error replies become `Error::Server`, and attributes are ignored.
+/// Numbers and booleans are also read from strings, since RESP2 sends them as bulk strings
+/// (e.g. `GET` on a counter).
+pub trait FromRespValue: Sized {
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self>;
+}
+
+/// The error returned when the reply can't be converted into the requested type.
+/// Parameters:
+/// - expected - The name of the requested type.
+/// - value - The reply.
+fn invalid_type(expected: &str, value: &RespDataTypeValue) -> Error {
+    let found = match value {
+        RespDataTypeValue::String(_) => "string",
+        RespDataTypeValue::Bytes(_) => "bytes",
+        RespDataTypeValue::VerbatimBytes(_, _) => "verbatim bytes",
+        RespDataTypeValue::VerbatimString(_, _) => "verbatim string",
+        RespDataTypeValue::Error(_, _) => "error",
+        RespDataTypeValue::Boolean(_) => "boolean",
+        RespDataTypeValue::Integer(_) => "integer",
+        RespDataTypeValue::Double(_)
+        | RespDataTypeValue::Infinity
+        | RespDataTypeValue::NegativeInfinity
+        | RespDataTypeValue::Nan => "double",
+        RespDataTypeValue::BigNumber(_) => "big number",
+        RespDataTypeValue::Array(_) => "array",
+        RespDataTypeValue::Object(_) => "map",
+        RespDataTypeValue::Set(_) => "set",
+        RespDataTypeValue::Push(_) => "push",
+        RespDataTypeValue::Attributed(_, _) => "attributes",
+        RespDataTypeValue::Null => "null",
+    };
+    Error::UnexpectedReply(format!(
+        "REPLY_TYPE_INVALID: expected {expected}, found {found}"
+    ))
+}
+
+fn out_of_range(expected: &str) -> Error {
+    Error::UnexpectedReply(format!("REPLY_VALUE_OUT_OF_RANGE: {expected}"))
+}
+
+impl FromRespValue for RespDataTypeValue {
+    /// Returns the reply as is, error replies included.
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        Ok(value)
+    }
+}
+
+impl FromRespValue for () {
+    /// Accepts any reply that is not an error, e.g. the `OK` of `SET`.
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        value.into_reply()?;
+        Ok(())
+    }
+}
+
+impl FromRespValue for bool {
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        match value.into_reply()? {
+            RespDataTypeValue::Boolean(value) => Ok(value),
+            RespDataTypeValue::Integer(0) => Ok(false),
+            RespDataTypeValue::Integer(1) => Ok(true),
+            RespDataTypeValue::String(value) => match RespDataTypeValue::parse_bool(&value) {
+                Some(value) => Ok(value),
+                None => Err(invalid_type("bool", &RespDataTypeValue::String(value))),
+            },
+            value => Err(invalid_type("bool", &value)),
+        }
+    }
+}
+
+macro_rules! from_resp_value_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl FromRespValue for $ty {
+                fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+                    let expected = stringify!($ty);
+                    match value.into_reply()? {
+                        RespDataTypeValue::Integer(value) => {
+                            <$ty>::try_from(value).map_err(|_| out_of_range(expected))
+                        }
+                        RespDataTypeValue::BigNumber(value) => {
+                            <$ty>::try_from(&value).map_err(|_| out_of_range(expected))
+                        }
+                        RespDataTypeValue::String(value) => match value.parse::<$ty>() {
+                            Ok(value) => Ok(value),
+                            Err(_) => Err(invalid_type(expected, &RespDataTypeValue::String(value))),
+                        },
+                        value => Err(invalid_type(expected, &value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_resp_value_integer!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl FromRespValue for f64 {
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        match value.into_reply()? {
+            RespDataTypeValue::Double(value) => Ok(value.0),
+            RespDataTypeValue::Infinity => Ok(f64::INFINITY),
+            RespDataTypeValue::NegativeInfinity => Ok(f64::NEG_INFINITY),
+            RespDataTypeValue::Nan => Ok(f64::NAN),
+            RespDataTypeValue::Integer(value) => Ok(value as f64),
+            RespDataTypeValue::String(value) => match value.parse::<f64>() {
+                Ok(value) => Ok(value),
+                Err(_) => Err(invalid_type("f64", &RespDataTypeValue::String(value))),
+            },
+            value => Err(invalid_type("f64", &value)),
+        }
+    }
+}
+
+impl FromRespValue for f32 {
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        Ok(f64::from_resp_value(value)? as f32)
+    }
+}
+
+impl FromRespValue for OrderedFloat<f64> {
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        Ok(OrderedFloat(f64::from_resp_value(value)?))
+    }
+}
+
+impl FromRespValue for BigInt {
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        match value.into_reply()? {
+            RespDataTypeValue::BigNumber(value) => Ok(value),
+            RespDataTypeValue::Integer(value) => Ok(BigInt::from(value)),
+            RespDataTypeValue::String(value) => match value.parse::<BigInt>() {
+                Ok(value) => Ok(value),
+                Err(_) => Err(invalid_type("BigInt", &RespDataTypeValue::String(value))),
+            },
+            value => Err(invalid_type("BigInt", &value)),
+        }
+    }
+}
+
+impl FromRespValue for String {
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        match value.into_reply()? {
+            RespDataTypeValue::String(value) | RespDataTypeValue::VerbatimString(value, _) => {
+                Ok(value)
+            }
+            RespDataTypeValue::Integer(value) => Ok(value.to_string()),
+            RespDataTypeValue::Double(value) => Ok(value.to_string()),
+            RespDataTypeValue::BigNumber(value) => Ok(value.to_string()),
+            value => Err(invalid_type("String", &value)),
+        }
+    }
+}
+
+impl FromRespValue for Bytes {
+    /// Returns the raw payload of a string, whether it is valid UTF-8 or not, e.g. a serialized
+    /// value stored with `SetBytes`.
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        match value.into_reply()? {
+            RespDataTypeValue::Bytes(value) | RespDataTypeValue::VerbatimBytes(value, _) => {
+                Ok(Bytes::from(value))
+            }
+            RespDataTypeValue::String(value) | RespDataTypeValue::VerbatimString(value, _) => {
+                Ok(Bytes::from(value))
+            }
+            value => Err(invalid_type("Bytes", &value)),
+        }
+    }
+}
+
+impl<T: FromRespValue> FromRespValue for Option<T> {
+    /// Null replies, e.g. `GET` on a missing key, become `None`.
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        match value.into_reply()? {
+            RespDataTypeValue::Null => Ok(None),
+            value => Ok(Some(T::from_resp_value(value)?)),
+        }
+    }
+}
+
+impl<T: FromRespValue> FromRespValue for Vec<T> {
+    /// Arrays, sets and pushes are converted element by element, a null array is empty.
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        match value.into_reply()? {
+            RespDataTypeValue::Array(values) | RespDataTypeValue::Push(values) => {
+                values.into_iter().map(T::from_resp_value).collect()
+            }
+            RespDataTypeValue::Set(values) => values.into_iter().map(T::from_resp_value).collect(),
+            RespDataTypeValue::Null => Ok(Vec::new()),
+            value => Err(invalid_type("Vec", &value)),
+        }
+    }
+}
+
+impl<K: FromRespValue + Eq + Hash, V: FromRespValue> FromRespValue for HashMap<K, V> {
+    /// Maps, and RESP2 flat arrays of field/value pairs (e.g. `HGETALL`), are converted entry by entry.
+    fn from_resp_value(value: RespDataTypeValue) -> crate::Result<Self> {
+        match value.into_reply()? {
+            RespDataTypeValue::Object(values) => values
+                .into_iter()
+                .map(|(key, value)| Ok((K::from_resp_value(key)?, V::from_resp_value(value)?)))
+                .collect(),
+            RespDataTypeValue::Array(values) if values.len() % 2 == 0 => {
+                let mut values = values.into_iter();
+                let mut data = HashMap::with_capacity(values.len() / 2);
+                while let (Some(key), Some(value)) = (values.next(), values.next()) {
+                    data.insert(K::from_resp_value(key)?, V::from_resp_value(value)?);
+                }
+                Ok(data)
+            }
+            RespDataTypeValue::Null => Ok(HashMap::new()),
+            value => Err(invalid_type("HashMap", &value)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_from_resp_value {
+    use super::*;
+    use crate::types::RespErrorKind;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    fn string(value: &str) -> RespDataTypeValue {
+        RespDataTypeValue::String(value.to_string())
+    }
+
+    #[test]
+    fn test_scalars() {
+        assert_eq!(
+            42,
+            i64::from_resp_value(RespDataTypeValue::Integer(42)).unwrap()
+        );
+        assert_eq!(42, u32::from_resp_value(string("42")).unwrap());
+        assert_eq!(1.5, f64::from_resp_value(string("1.5")).unwrap());
+        assert_eq!(
+            OrderedFloat(f64::INFINITY),
+            OrderedFloat::<f64>::from_resp_value(RespDataTypeValue::Infinity).unwrap()
+        );
+        assert!(bool::from_resp_value(RespDataTypeValue::Integer(1)).unwrap());
+        assert!(!bool::from_resp_value(RespDataTypeValue::Boolean(false)).unwrap());
+        assert!(bool::from_resp_value(string("true")).unwrap());
+        assert!(bool::from_resp_value(string("yes")).is_err());
+        assert_eq!("OK", String::from_resp_value(string("OK")).unwrap());
+        assert_eq!(
+            BigInt::from(i64::MAX) * 2,
+            BigInt::from_resp_value(RespDataTypeValue::BigNumber(BigInt::from(i64::MAX) * 2))
+                .unwrap()
+        );
+        assert!(<()>::from_resp_value(string("OK")).is_ok());
+        assert_eq!(
+            Bytes::from_static(&[0, 159, 146, 150, 255]),
+            Bytes::from_resp_value(RespDataTypeValue::Bytes(vec![0, 159, 146, 150, 255])).unwrap()
+        );
+        assert_eq!(
+            Bytes::from_static(b"hello"),
+            Bytes::from_resp_value(string("hello")).unwrap()
+        );
+        assert!(Bytes::from_resp_value(RespDataTypeValue::Integer(1)).is_err());
+        let attributed = RespDataTypeValue::Attributed(
+            BTreeMap::from([(string("ttl"), RespDataTypeValue::Integer(1))]),
+            Box::new(RespDataTypeValue::Integer(7)),
+        );
+        assert_eq!(7, usize::from_resp_value(attributed).unwrap());
+    }
+
+    #[test]
+    fn test_collections() {
+        assert_eq!(
+            None,
+            Option::<String>::from_resp_value(RespDataTypeValue::Null).unwrap()
+        );
+        assert_eq!(
+            Some("a".to_string()),
+            Option::<String>::from_resp_value(string("a")).unwrap()
+        );
+        assert_eq!(
+            vec![1, 2],
+            Vec::<i64>::from_resp_value(RespDataTypeValue::Set(BTreeSet::from([
+                RespDataTypeValue::Integer(2),
+                RespDataTypeValue::Integer(1),
+            ])))
+            .unwrap()
+        );
+        let expected = HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]);
+        let map = RespDataTypeValue::Object(BTreeMap::from([
+            (string("a"), RespDataTypeValue::Integer(1)),
+            (string("b"), RespDataTypeValue::Integer(2)),
+        ]));
+        assert_eq!(
+            expected,
+            HashMap::<String, i64>::from_resp_value(map).unwrap()
+        );
+        let flat =
+            RespDataTypeValue::Array(vec![string("a"), string("1"), string("b"), string("2")]);
+        assert_eq!(
+            expected,
+            HashMap::<String, i64>::from_resp_value(flat).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let result = i64::from_resp_value(RespDataTypeValue::Error(
+            RespErrorKind::WrongType,
+            "wrong kind".to_string(),
+        ));
+        assert!(
+            matches!(result, Err(Error::Server(RespErrorKind::WrongType, _))),
+            "{:#?}",
+            result
+        );
+        let result = u8::from_resp_value(RespDataTypeValue::Integer(300));
+        assert!(
+            matches!(&result, Err(Error::UnexpectedReply(message)) if message.starts_with("REPLY_VALUE_OUT_OF_RANGE")),
+            "{:#?}",
+            result
+        );
+        let result = i64::from_resp_value(RespDataTypeValue::Array(vec![]));
+        assert!(
+            matches!(&result, Err(Error::UnexpectedReply(message)) if message == "REPLY_TYPE_INVALID: expected i64, found array"),
+            "{:#?}",
+            result
+        );
+        let result = Vec::<i64>::from_resp_value(RespDataTypeValue::Array(vec![string("x")]));
+        assert!(result.is_err());
+    }
+}
@@ -1,11 +1,23 @@
 pub mod command_kind;
+pub mod expected_replies;
 pub mod expiry_kind;
+pub mod from_resp_value;
+pub mod protocol_version;
 pub mod resp_data_kind;
 pub mod resp_data_type_iter;
 pub mod resp_data_type_value;
+pub mod resp_error_details;
 pub mod resp_error_kind;
+pub mod resp_value_ref;
+pub mod server_info;
 pub mod verbatim_encoding;
+pub use expected_replies::ExpectedReplies;
 pub use expiry_kind::ExpiryKind;
+pub use from_resp_value::FromRespValue;
+pub use protocol_version::ProtocolVersion;
 pub use resp_data_type_value::RespDataTypeValue;
+pub use resp_error_details::RespErrorDetails;
 pub use resp_error_kind::RespErrorKind;
+pub use resp_value_ref::RespValueRef;
+pub use server_info::ServerInfo;
 pub use verbatim_encoding::VerbatimEncoding;
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// The RESP protocol version negotiated with the server. See https://valkey.io/commands/hello/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProtocolVersion {
+    #[default]
+    Resp2,
+    Resp3,
+}
+
+impl std::fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Self::Resp2 => "2",
+            Self::Resp3 => "3",
+        };
+        write!(f, "{value}")
+    }
+}
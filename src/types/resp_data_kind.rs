@@ -1,4 +1,4 @@
-use anyhow::anyhow;
+use crate::{Error, ProtocolCode};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EnumIter)]
@@ -15,6 +15,9 @@ pub enum RespDataType {
     BulkErrors,
     VerbatimStrings,
     Maps,
+    Sets,
+    Pushes,
+    Attributes,
 }
 
 impl RespDataType {
@@ -22,6 +25,18 @@ impl RespDataType {
         matches!(self, Self::Maps)
     }
 
+    pub fn is_sets(&self) -> bool {
+        matches!(self, Self::Sets)
+    }
+
+    pub fn is_pushes(&self) -> bool {
+        matches!(self, Self::Pushes)
+    }
+
+    pub fn is_attributes(&self) -> bool {
+        matches!(self, Self::Attributes)
+    }
+
     pub fn is_simple_strings(&self) -> bool {
         matches!(self, Self::SimpleStrings)
     }
@@ -79,7 +94,7 @@ impl RespDataType {
     // Identify the resp type variant
     // Parameters
     // - The decimal value of the resp type. See https://valkey.io/topics/protocol
-    pub fn identify(value: u8) -> anyhow::Result<Self> {
+    pub fn identify(value: u8) -> crate::Result<Self> {
         let value = match value {
             43 => Self::SimpleStrings,
             45 => Self::SimpleErrors,
@@ -93,15 +108,18 @@ impl RespDataType {
             33 => Self::BulkErrors,
             61 => Self::VerbatimStrings,
             37 => Self::Maps,
+            126 => Self::Sets,
+            62 => Self::Pushes,
+            124 => Self::Attributes,
             _ => {
-                return Err(anyhow!("NOT_SUPPORTED"));
+                return Err(Error::protocol(ProtocolCode::NotSupported, 0));
             }
         };
         Ok(value)
     }
 
     // Converts the resp type variant to decimal
-    pub fn to_decimal(&self) -> anyhow::Result<u8> {
+    pub fn to_decimal(&self) -> crate::Result<u8> {
         let value = match self {
             Self::SimpleStrings => 43,
             Self::SimpleErrors => 45,
@@ -114,6 +132,9 @@ impl RespDataType {
             Self::BigNumbers => 40,
             Self::BulkErrors => 33,
             Self::Maps => 37,
+            Self::Sets => 126,
+            Self::Pushes => 62,
+            Self::Attributes => 124,
             Self::VerbatimStrings => 61,
         };
         Ok(value)
@@ -145,6 +166,12 @@ pub mod test_resp_data_type {
                 result_is_error: false,
                 assert_is_error: false,
             },
+            TestCase {
+                input: 126,
+                expected: RespDataType::Sets,
+                result_is_error: false,
+                assert_is_error: false,
+            },
             TestCase {
                 input: 45,
                 expected: RespDataType::SimpleStrings,
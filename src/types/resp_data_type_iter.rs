@@ -11,36 +11,26 @@ impl<'a> RespDataTypeIterator<'a> {
         Self { values, current: 0 }
     }
 
-    pub fn nnext(&mut self, n: usize) -> Option<Vec<u8>> {
-        let mut current = self.current;
-        let length = self.values.len();
-        let start = current;
-        let end = current + n;
-        let mut data: Vec<u8> = Vec::new();
-        for i in start..end {
-            if i < length {
-                data.push(self.values[current]);
-            }
-            current += 1;
-        }
-        self.current = current;
+    /// Returns the next `n` values, or fewer at the end of the input, and advances past them.
+    pub fn nnext(&mut self, n: usize) -> Option<&'a [u8]> {
+        let data = self.slice(n);
+        self.current += n;
         Some(data)
     }
 
-    pub fn npeek(&self, n: usize) -> Option<Vec<u8>> {
-        let current = self.current;
-        let length = self.values.len();
-        let start = current;
-        let end = current + n;
-        let mut data: Vec<u8> = Vec::new();
-        for i in start..end {
-            if i < length {
-                data.push(self.values[i]);
-            }
-        }
+    /// Returns the next `n` values, or fewer at the end of the input, without advancing.
+    pub fn npeek(&self, n: usize) -> Option<&'a [u8]> {
+        let data = self.slice(n);
         if data.is_empty() { None } else { Some(data) }
     }
 
+    fn slice(&self, n: usize) -> &'a [u8] {
+        let length = self.values.len();
+        let start = self.current.min(length);
+        let end = self.current.saturating_add(n).min(length);
+        &self.values[start..end]
+    }
+
     pub fn get_data_type(&self) -> Option<RespDataType> {
         if self.current < self.values.len()
             && let value = &self.values[self.current]
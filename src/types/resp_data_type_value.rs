@@ -1,8 +1,9 @@
-use crate::types::{RespErrorKind, VerbatimEncoding};
+use crate::types::command_kind::is_subscription_command;
+use crate::types::{RespErrorDetails, RespErrorKind, VerbatimEncoding};
 use num_bigint::BigInt;
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum RespDataTypeValue {
     Array(Vec<RespDataTypeValue>),
@@ -14,24 +15,115 @@ pub enum RespDataTypeValue {
     Double(OrderedFloat<f64>),
     BigNumber(BigInt),
     Object(BTreeMap<RespDataTypeValue, RespDataTypeValue>),
+    Set(BTreeSet<RespDataTypeValue>),
+    Push(Vec<RespDataTypeValue>),
+    /// A value preceded by RESP3 attributes, e.g. key popularity or tracing metadata.
+    Attributed(
+        BTreeMap<RespDataTypeValue, RespDataTypeValue>,
+        Box<RespDataTypeValue>,
+    ),
     Null,
     Infinity,
     NegativeInfinity,
     Nan,
+    /// A bulk string whose payload is not valid UTF-8.
+    Bytes(Vec<u8>),
+    /// A verbatim string whose payload is not valid UTF-8, with its encoding.
+    VerbatimBytes(Vec<u8>, VerbatimEncoding),
 }
 
 impl RespDataTypeValue {
     pub fn is_array(&self) -> bool {
         matches!(self, Self::Array(_))
     }
+    pub fn is_set(&self) -> bool {
+        matches!(self, Self::Set(_))
+    }
+    pub fn is_push(&self) -> bool {
+        matches!(self, Self::Push(_))
+    }
+    /// Checks if the value is the push confirming a subscription change for one channel, e.g.
+    /// `>3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n`. It is usually the reply to a subscription
+    /// command, but the server also sends them on its own, e.g. `sunsubscribe` when a slot moves.
+    pub fn is_subscription_confirmation(&self) -> bool {
+        self.subscription_confirmation().is_some()
+    }
+    /// Returns the kind and the channel of a subscription confirmation, e.g. `subscribe` and
+    /// `news`. The channel is `None` for an `UNSUBSCRIBE` sent without any subscription.
+    pub fn subscription_confirmation(&self) -> Option<(&[u8], Option<&[u8]>)> {
+        match self.without_attributes() {
+            Self::Push(values) => {
+                let kind = values.first()?.as_bytes()?;
+                if !is_subscription_command(kind) {
+                    return None;
+                }
+                Some((kind, values.get(1).and_then(|channel| channel.as_bytes())))
+            }
+            _ => None,
+        }
+    }
+    pub fn is_attributed(&self) -> bool {
+        matches!(self, Self::Attributed(_, _))
+    }
+    /// Returns the attributes sent with the value, if any.
+    pub fn attributes(&self) -> Option<&BTreeMap<RespDataTypeValue, RespDataTypeValue>> {
+        match self {
+            Self::Attributed(attributes, _) => Some(attributes),
+            _ => None,
+        }
+    }
+    /// Returns the value without its attributes.
+    pub fn without_attributes(&self) -> &Self {
+        match self {
+            Self::Attributed(_, value) => value.without_attributes(),
+            _ => self,
+        }
+    }
+    /// Drops the attributes of the reply, and turns an error reply into `Error::Server`.
+    /// `FromRespValue` and `from_resp` both go through it, so an error reply fails the same way
+    /// whatever the requested type.
+    pub fn into_reply(self) -> crate::Result<Self> {
+        match self {
+            Self::Attributed(_, value) => value.into_reply(),
+            Self::Error(kind, message) => Err(crate::Error::Server(kind, message)),
+            value => Ok(value),
+        }
+    }
+    /// Reads a boolean sent as a string, e.g. a RESP2 reply or a hash field: `0`, `1`, `false`
+    /// or `true`.
+    pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+        match value {
+            "0" | "false" => Some(false),
+            "1" | "true" => Some(true),
+            _ => None,
+        }
+    }
     pub fn is_string(&self) -> bool {
         matches!(self, Self::String(_))
     }
+    pub fn is_bytes(&self) -> bool {
+        matches!(self, Self::Bytes(_) | Self::VerbatimBytes(_, _))
+    }
+    /// Returns the raw payload of a string value, whether it is valid UTF-8 or not.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::String(value) | Self::VerbatimString(value, _) => Some(value.as_bytes()),
+            Self::Bytes(value) | Self::VerbatimBytes(value, _) => Some(value),
+            _ => None,
+        }
+    }
     pub fn is_error(&self) -> bool {
         matches!(self, Self::Error(_, _))
     }
+    /// Returns the typed payload of an error reply, e.g. the slot and address of `MOVED`.
+    pub fn error_details(&self) -> Option<RespErrorDetails> {
+        match self.without_attributes() {
+            Self::Error(kind, message) => RespErrorDetails::parse(*kind, message),
+            _ => None,
+        }
+    }
     pub fn is_verbatim_string(&self) -> bool {
-        matches!(self, Self::VerbatimString(_, _))
+        matches!(self, Self::VerbatimString(_, _) | Self::VerbatimBytes(_, _))
     }
     pub fn is_boolean(&self) -> bool {
         matches!(self, Self::Boolean(_))
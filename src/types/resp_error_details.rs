@@ -0,0 +1,151 @@
+use crate::types::RespErrorKind;
+
+/// The payload of the error replies a client can act on, parsed from the error message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RespErrorDetails {
+    /// `MOVED <slot> <address>`, the slot is now served by `address`.
+    /// The command, and the next ones for the slot, must be sent there.
+    Moved { slot: u16, address: String },
+    /// `ASK <slot> <address>`, the slot is being migrated to `address`.
+    /// Only this command must be sent there, preceded by `ASKING`.
+    Ask { slot: u16, address: String },
+    /// `TRYAGAIN`, a multi-key command hit a slot being resharded. Retry after a short delay.
+    TryAgain,
+    /// `LOADING`, the server is loading the dataset in memory. Retry once it is loaded.
+    Loading,
+    /// `BUSY`, a script or function is running. Retry once it ends, only `SCRIPT KILL`,
+    /// `FUNCTION KILL` and `SHUTDOWN NOSAVE` are accepted meanwhile.
+    Busy,
+}
+
+impl RespErrorDetails {
+    /// Parses the details of an error reply.
+    /// Parameters:
+    /// - kind - The kind of the error.
+    /// - message - The message, without the prefix of the kind.
+    ///   Returns:
+    /// - None - The kind has no details, or the `MOVED`/`ASK` message is malformed.
+    pub fn parse(kind: RespErrorKind, message: &str) -> Option<Self> {
+        match kind {
+            RespErrorKind::Moved | RespErrorKind::Ask => {
+                let (slot, address) = message.trim().split_once(' ')?;
+                let slot = slot.parse::<u16>().ok()?;
+                let address = address.trim().to_string();
+                if address.is_empty() || address.contains(' ') {
+                    return None;
+                }
+                if kind == RespErrorKind::Moved {
+                    Some(Self::Moved { slot, address })
+                } else {
+                    Some(Self::Ask { slot, address })
+                }
+            }
+            RespErrorKind::TryAgain => Some(Self::TryAgain),
+            RespErrorKind::Loading => Some(Self::Loading),
+            RespErrorKind::Busy => Some(Self::Busy),
+            _ => None,
+        }
+    }
+
+    /// Returns the slot and the address the command must be sent to, for `MOVED` and `ASK`.
+    pub fn redirect(&self) -> Option<(u16, &str)> {
+        match self {
+            Self::Moved { slot, address } | Self::Ask { slot, address } => {
+                Some((*slot, address.as_str()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the same command can succeed when sent again to the same server, after a delay.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::TryAgain | Self::Loading | Self::Busy)
+    }
+}
+
+#[cfg(test)]
+pub mod test_resp_error_details {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        struct TestCase {
+            pub kind: RespErrorKind,
+            pub message: &'static str,
+            pub expected: Option<RespErrorDetails>,
+        }
+        let test_cases = vec![
+            TestCase {
+                kind: RespErrorKind::Moved,
+                message: "3999 127.0.0.1:6381",
+                expected: Some(RespErrorDetails::Moved {
+                    slot: 3999,
+                    address: "127.0.0.1:6381".to_string(),
+                }),
+            },
+            TestCase {
+                kind: RespErrorKind::Ask,
+                message: "3999 valkey-2.internal:6379",
+                expected: Some(RespErrorDetails::Ask {
+                    slot: 3999,
+                    address: "valkey-2.internal:6379".to_string(),
+                }),
+            },
+            TestCase {
+                // The endpoint is unknown, the host is the one of the current connection.
+                kind: RespErrorKind::Moved,
+                message: "12 :6380",
+                expected: Some(RespErrorDetails::Moved {
+                    slot: 12,
+                    address: ":6380".to_string(),
+                }),
+            },
+            TestCase {
+                kind: RespErrorKind::Moved,
+                message: "abc 127.0.0.1:6381",
+                expected: None,
+            },
+            TestCase {
+                kind: RespErrorKind::Ask,
+                message: "3999",
+                expected: None,
+            },
+            TestCase {
+                kind: RespErrorKind::TryAgain,
+                message: "Multiple keys request during rehashing of slot",
+                expected: Some(RespErrorDetails::TryAgain),
+            },
+            TestCase {
+                kind: RespErrorKind::Loading,
+                message: "Valkey is loading the dataset in memory",
+                expected: Some(RespErrorDetails::Loading),
+            },
+            TestCase {
+                kind: RespErrorKind::Busy,
+                message: "Valkey is busy running a script.",
+                expected: Some(RespErrorDetails::Busy),
+            },
+            TestCase {
+                kind: RespErrorKind::Err,
+                message: "unknown command",
+                expected: None,
+            },
+        ];
+        for test_case in test_cases {
+            let result = RespErrorDetails::parse(test_case.kind, test_case.message);
+            assert_eq!(test_case.expected, result, "{}", test_case.message);
+        }
+    }
+
+    #[test]
+    fn test_hints() {
+        let moved = RespErrorDetails::Moved {
+            slot: 1,
+            address: "127.0.0.1:6381".to_string(),
+        };
+        assert_eq!(Some((1, "127.0.0.1:6381")), moved.redirect());
+        assert!(!moved.is_retryable());
+        assert!(RespErrorDetails::Loading.is_retryable());
+        assert_eq!(None, RespErrorDetails::TryAgain.redirect());
+    }
+}
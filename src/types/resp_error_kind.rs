@@ -40,19 +40,61 @@ pub enum RespErrorKind {
 }
 
 impl RespErrorKind {
+    /// Splits an error reply into its kind and message, e.g. `WRONGTYPE Operation against a key`.
+    /// The first word is the kind only when it is a known uppercase prefix followed by a space or
+    /// by the end of the error. Otherwise the kind is `Unknown` and the whole error is the message.
     pub fn parse(data: String) -> RespDataTypeValue {
-        let mut error: Vec<String> = Vec::new();
-        for c in data.chars() {
-            if c.is_uppercase() && !c.is_whitespace() {
-                error.push(c.to_string());
-            } else {
-                break;
-            }
+        let (prefix, message) = data.split_once(' ').unwrap_or((&data, ""));
+        let kind = if prefix.chars().all(|c| c.is_ascii_uppercase()) {
+            Self::from(prefix)
+        } else {
+            Self::Unknown
+        };
+        let message = match kind {
+            Self::Unknown => data.trim(),
+            _ => message.trim(),
+        };
+        RespDataTypeValue::Error(kind, message.to_owned())
+    }
+
+    /// Returns the prefix the server sends for this kind, empty for `Unknown`.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::Err => "ERR",
+            Self::WrongType => "WRONGTYPE",
+            Self::NoAuth => "NOAUTH",
+            Self::WrongPass => "WRONGPASS",
+            Self::NoPerm => "NOPERM",
+            Self::Busy => "BUSY",
+            Self::NoScript => "NOSCRIPT",
+            Self::Oom => "OOM",
+            Self::ExecAbort => "EXECABORT",
+            Self::Loading => "LOADING",
+            Self::MasterDown => "MASTERDOWN",
+            Self::ReadOnly => "READONLY",
+            Self::Misconf => "MISCONF",
+            Self::ClusterDown => "CLUSTERDOWN",
+            Self::Moved => "MOVED",
+            Self::Ask => "ASK",
+            Self::TryAgain => "TRYAGAIN",
+            Self::CrossSlot => "CROSSSLOT",
+            Self::Unloading => "UNLOADING",
+            Self::Index => "INDEX",
+            Self::Proto => "PROTO",
+            Self::Auth => "AUTH",
+            Self::Syntax => "SYNTAX",
+            Self::Exec => "EXEC",
+            Self::Link => "LINK",
+            Self::BusyKey => "BUSYKEY",
+            Self::NoSave => "NOSAVE",
+            Self::NoPubSub => "NOPUBSUB",
+            Self::Timeout => "TIMEOUT",
+            Self::Config => "CONFIG",
+            Self::ReplConf => "REPLCONF",
+            Self::Asking => "ASKING",
+            Self::Redis => "REDIS",
+            Self::Unknown => "",
         }
-        let error: String = error.join("");
-        let kind = Self::from(&error);
-        let message = data.replace(&error, "").trim().to_owned();
-        RespDataTypeValue::Error(kind, message)
     }
 
     pub fn from(value: &str) -> Self {
@@ -94,3 +136,74 @@ impl RespErrorKind {
         }
     }
 }
+
+#[cfg(test)]
+pub mod test_resp_error_kind {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        struct TestCase {
+            pub input: &'static str,
+            pub expected: RespDataTypeValue,
+        }
+        let test_cases = vec![
+            TestCase {
+                input: "WRONGTYPE Operation against a key holding the wrong kind of value",
+                expected: RespDataTypeValue::Error(
+                    RespErrorKind::WrongType,
+                    "Operation against a key holding the wrong kind of value".to_string(),
+                ),
+            },
+            TestCase {
+                input: "MOVED 3999 127.0.0.1:6381",
+                expected: RespDataTypeValue::Error(
+                    RespErrorKind::Moved,
+                    "3999 127.0.0.1:6381".to_string(),
+                ),
+            },
+            TestCase {
+                input: "ERR",
+                expected: RespDataTypeValue::Error(RespErrorKind::Err, "".to_string()),
+            },
+            TestCase {
+                // The prefix is not followed by a space, it is not a prefix.
+                input: "ERRORS happened",
+                expected: RespDataTypeValue::Error(
+                    RespErrorKind::Unknown,
+                    "ERRORS happened".to_string(),
+                ),
+            },
+            TestCase {
+                input: "ASKING: not a prefix",
+                expected: RespDataTypeValue::Error(
+                    RespErrorKind::Unknown,
+                    "ASKING: not a prefix".to_string(),
+                ),
+            },
+            TestCase {
+                // Only the leading prefix is removed from the message.
+                input: "ERR unknown command 'ERR'",
+                expected: RespDataTypeValue::Error(
+                    RespErrorKind::Err,
+                    "unknown command 'ERR'".to_string(),
+                ),
+            },
+            TestCase {
+                input: "Err lowercase",
+                expected: RespDataTypeValue::Error(
+                    RespErrorKind::Unknown,
+                    "Err lowercase".to_string(),
+                ),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                RespErrorKind::parse(test_case.input.to_string()),
+                "{}",
+                test_case.input
+            );
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::types::{RespDataTypeValue, RespErrorKind, VerbatimEncoding};
+use num_bigint::BigInt;
+use ordered_float::OrderedFloat;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A parsed RESP value that borrows its payloads from the input buffer instead of copying them.
+/// Use `to_owned` to convert it into a `RespDataTypeValue` once the buffer can't be kept around.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RespValueRef<'a> {
+    SimpleString(&'a [u8]),
+    BulkString(&'a [u8]),
+    /// The chunks of a RESP3 streamed string, in order.
+    StreamedString(Vec<&'a [u8]>),
+    /// The encoding prefix (e.g. `txt`) and the payload.
+    VerbatimString(&'a [u8], &'a [u8]),
+    SimpleError(&'a [u8]),
+    BulkError(&'a [u8]),
+    Integer(i64),
+    Double(f64),
+    Boolean(bool),
+    /// The digits of the number, with an optional leading `-`.
+    BigNumber(&'a [u8]),
+    Null,
+    Array(Vec<RespValueRef<'a>>),
+    Map(Vec<(RespValueRef<'a>, RespValueRef<'a>)>),
+    Set(Vec<RespValueRef<'a>>),
+    Push(Vec<RespValueRef<'a>>),
+    Attributed(
+        Vec<(RespValueRef<'a>, RespValueRef<'a>)>,
+        Box<RespValueRef<'a>>,
+    ),
+}
+
+impl<'a> RespValueRef<'a> {
+    /// Returns the payload of a string value without copying it.
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::SimpleString(value)
+            | Self::BulkString(value)
+            | Self::VerbatimString(_, value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the payload of a string value if it is valid UTF-8, without copying it.
+    pub fn as_str(&self) -> Option<&'a str> {
+        std::str::from_utf8(self.as_bytes()?).ok()
+    }
+
+    /// Copies the value into a `RespDataTypeValue`, the same value `RespParser` builds from the input.
+    pub fn to_owned(&self) -> RespDataTypeValue {
+        match self {
+            Self::SimpleString(value) => {
+                RespDataTypeValue::String(String::from_utf8_lossy(value).to_string())
+            }
+            Self::BulkString(value) => match String::from_utf8(value.to_vec()) {
+                Ok(value) => RespDataTypeValue::String(value),
+                Err(err) => RespDataTypeValue::Bytes(err.into_bytes()),
+            },
+            Self::StreamedString(chunks) => match String::from_utf8(chunks.concat()) {
+                Ok(value) => RespDataTypeValue::String(value),
+                Err(err) => RespDataTypeValue::Bytes(err.into_bytes()),
+            },
+            Self::VerbatimString(encoding, value) => {
+                let encoding = VerbatimEncoding::from(&String::from_utf8_lossy(encoding));
+                match String::from_utf8(value.to_vec()) {
+                    Ok(value) => RespDataTypeValue::VerbatimString(value, encoding),
+                    Err(err) => RespDataTypeValue::VerbatimBytes(err.into_bytes(), encoding),
+                }
+            }
+            Self::SimpleError(value) | Self::BulkError(value) => {
+                RespErrorKind::parse(String::from_utf8_lossy(value).to_string())
+            }
+            Self::Integer(value) => RespDataTypeValue::Integer(*value),
+            Self::Double(value) => {
+                if value.is_nan() {
+                    RespDataTypeValue::Nan
+                } else if value.is_infinite() && value.is_sign_positive() {
+                    RespDataTypeValue::Infinity
+                } else if value.is_infinite() && value.is_sign_negative() {
+                    RespDataTypeValue::NegativeInfinity
+                } else {
+                    RespDataTypeValue::Double(OrderedFloat(*value))
+                }
+            }
+            Self::Boolean(value) => RespDataTypeValue::Boolean(*value),
+            // The digits were validated by the parser.
+            Self::BigNumber(value) => {
+                RespDataTypeValue::BigNumber(BigInt::parse_bytes(value, 10).unwrap_or_default())
+            }
+            Self::Null => RespDataTypeValue::Null,
+            Self::Array(values) => {
+                RespDataTypeValue::Array(values.iter().map(Self::to_owned).collect())
+            }
+            Self::Map(values) => RespDataTypeValue::Object(Self::to_owned_map(values)),
+            Self::Set(values) => RespDataTypeValue::Set(
+                values
+                    .iter()
+                    .map(Self::to_owned)
+                    .collect::<BTreeSet<RespDataTypeValue>>(),
+            ),
+            Self::Push(values) => {
+                RespDataTypeValue::Push(values.iter().map(Self::to_owned).collect())
+            }
+            Self::Attributed(attributes, value) => RespDataTypeValue::Attributed(
+                Self::to_owned_map(attributes),
+                Box::new(Self::to_owned(value)),
+            ),
+        }
+    }
+
+    fn to_owned_map(
+        values: &[(RespValueRef<'a>, RespValueRef<'a>)],
+    ) -> BTreeMap<RespDataTypeValue, RespDataTypeValue> {
+        values
+            .iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect()
+    }
+}
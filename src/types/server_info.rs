@@ -0,0 +1,128 @@
+use crate::Error;
+use crate::types::RespDataTypeValue;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The server details returned by `HELLO`. See https://valkey.io/commands/hello/
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerInfo {
+    pub server: String,
+    pub version: String,
+    pub proto: i64,
+    pub id: i64,
+    pub mode: String,
+    pub role: String,
+    pub modules: Vec<RespDataTypeValue>,
+}
+
+impl ServerInfo {
+    /// Reads the `HELLO` reply, either a RESP3 map or the flat key/value array sent over RESP2.
+    pub fn parse(value: &RespDataTypeValue) -> crate::Result<Self> {
+        let data: BTreeMap<RespDataTypeValue, RespDataTypeValue> = match value {
+            RespDataTypeValue::Object(data) => data.clone(),
+            RespDataTypeValue::Array(values) if values.len() % 2 == 0 => values
+                .chunks(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+            RespDataTypeValue::Error(kind, message) => {
+                return Err(Error::Server(*kind, message.to_owned()));
+            }
+            _ => {
+                return Err(Error::UnexpectedReply(
+                    "SERVER_INFO_INVALID_REPLY".to_string(),
+                ));
+            }
+        };
+        let get = |key: &str| data.get(&RespDataTypeValue::String(key.to_string()));
+        let get_string = |key: &str| match get(key) {
+            Some(RespDataTypeValue::String(value)) => value.to_owned(),
+            _ => String::new(),
+        };
+        let get_integer = |key: &str| match get(key) {
+            Some(RespDataTypeValue::Integer(value)) => *value,
+            _ => 0,
+        };
+        let modules = match get("modules") {
+            Some(RespDataTypeValue::Array(values)) => values.to_owned(),
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            server: get_string("server"),
+            version: get_string("version"),
+            proto: get_integer("proto"),
+            id: get_integer("id"),
+            mode: get_string("mode"),
+            role: get_string("role"),
+            modules,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test_server_info {
+    use super::*;
+    use crate::types::RespErrorKind;
+
+    fn string(value: &str) -> RespDataTypeValue {
+        RespDataTypeValue::String(value.to_string())
+    }
+
+    fn expected(proto: i64) -> ServerInfo {
+        ServerInfo {
+            server: "valkey".to_string(),
+            version: "8.0.1".to_string(),
+            proto,
+            id: 5,
+            mode: "standalone".to_string(),
+            role: "master".to_string(),
+            modules: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_resp3_map() {
+        let value = RespDataTypeValue::Object(BTreeMap::from([
+            (string("server"), string("valkey")),
+            (string("version"), string("8.0.1")),
+            (string("proto"), RespDataTypeValue::Integer(3)),
+            (string("id"), RespDataTypeValue::Integer(5)),
+            (string("mode"), string("standalone")),
+            (string("role"), string("master")),
+            (string("modules"), RespDataTypeValue::Array(vec![])),
+        ]));
+        let result = ServerInfo::parse(&value);
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(expected(3), result.unwrap());
+    }
+
+    #[test]
+    fn test_resp2_array() {
+        let value = RespDataTypeValue::Array(vec![
+            string("server"),
+            string("valkey"),
+            string("version"),
+            string("8.0.1"),
+            string("proto"),
+            RespDataTypeValue::Integer(2),
+            string("id"),
+            RespDataTypeValue::Integer(5),
+            string("mode"),
+            string("standalone"),
+            string("role"),
+            string("master"),
+            string("modules"),
+            RespDataTypeValue::Array(vec![]),
+        ]);
+        let result = ServerInfo::parse(&value);
+        assert!(result.is_ok(), "{:#?}", result.err());
+        assert_eq!(expected(2), result.unwrap());
+    }
+
+    #[test]
+    fn test_error() {
+        let value = RespDataTypeValue::Error(RespErrorKind::Unknown, "unsupported".to_string());
+        assert!(ServerInfo::parse(&value).is_err());
+        let value = RespDataTypeValue::Array(vec![string("server")]);
+        assert!(ServerInfo::parse(&value).is_err());
+    }
+}